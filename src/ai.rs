@@ -0,0 +1,505 @@
+//! This mod is for computer players.
+
+use crate::{
+    card::{Card, Color, Effect},
+    game::{Action, Event, PlayerStatus},
+    view::PlayerView,
+};
+
+mod adaptive;
+mod counting;
+mod expectiminimax;
+mod external;
+mod heuristic;
+mod linear;
+mod mcts;
+mod random;
+
+use std::{fmt::Display, str::FromStr};
+
+pub use adaptive::AdaptiveStrategy;
+pub use counting::CountingStrategy;
+pub use expectiminimax::ExpectiminimaxStrategy;
+pub use external::{echo_bot, ExternalStrategy, DEFAULT_TIMEOUT as DEFAULT_BOT_TIMEOUT};
+pub use heuristic::HeuristicStrategy;
+pub use linear::{LinearStrategy, Weights};
+pub use mcts::MctsStrategy;
+pub use random::RandomStrategy;
+
+/// Decides what a computer player does. The game keeps asking for actions until the player's turn
+/// is over, so drawing a card gets the strategy asked again with the drawn card in hand.
+pub trait Strategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action;
+
+    /// Chooses an action like `choose_action` does, along with why, for strategies that can say.
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        (self.choose_action(view), None)
+    }
+}
+
+/// Why a computer player made a move, for tuning strategies and showing new players how they
+/// think.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Explanation {
+    /// The move that was made, like "play a red 5".
+    pub action: String,
+    /// What the move was based on, most important first.
+    pub reasons: Vec<String>,
+    /// The other moves that were weighed up, with what each scored. Higher is better, and scores
+    /// only mean something next to each other.
+    pub scores: Vec<(String, f64)>,
+}
+
+/// How well a computer player plays.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Difficulty {
+    /// Plays the first card it can. It's how players without a strategy get played for them.
+    Basic,
+    /// Plays any card it can, at random.
+    Easy,
+    /// Plays by rules of thumb: holds on to wilds, goes after whoever is about to win, and gets
+    /// rid of cards worth a lot of points.
+    #[default]
+    Medium,
+    /// Keeps track of the cards that have been played.
+    Hard,
+    /// Searches ahead through the ways the game could go.
+    Expert,
+    /// Weighs up its moves with weights tuned by playing itself, loaded from the `weights`
+    /// setting.
+    Tuned,
+    /// Remembers how each human tends to play from game to game, and takes advantage of it.
+    Adaptive,
+    /// Made for two player games. Looks a few moves ahead, weighing up every card a draw could
+    /// turn up. Plays like a medium player in bigger games.
+    Duel,
+    /// Played by another program, set with the `bot` setting.
+    Bot,
+}
+
+impl Difficulty {
+    /// How a computer player at this difficulty picks its moves. Tuned players need their weights,
+    /// which only a config has, so here they use the default weights. Bots need a program to run,
+    /// so they fail here and have to come from a config instead.
+    pub fn strategy(&self) -> Result<Box<dyn Strategy>, String> {
+        Ok(match self {
+            Difficulty::Basic => Box::new(BasicStrategy),
+            Difficulty::Easy => Box::new(RandomStrategy),
+            Difficulty::Medium => Box::new(HeuristicStrategy),
+            Difficulty::Hard => Box::new(CountingStrategy::default()),
+            Difficulty::Expert => Box::new(MctsStrategy::default()),
+            Difficulty::Tuned => Box::new(LinearStrategy::default()),
+            Difficulty::Adaptive => Box::new(AdaptiveStrategy),
+            Difficulty::Duel => Box::new(ExpectiminimaxStrategy::default()),
+            Difficulty::Bot => return Err("Bot players need a command to run.".to_string()),
+        })
+    }
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Difficulty::Basic => write!(f, "basic"),
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Medium => write!(f, "medium"),
+            Difficulty::Hard => write!(f, "hard"),
+            Difficulty::Expert => write!(f, "expert"),
+            Difficulty::Tuned => write!(f, "tuned"),
+            Difficulty::Adaptive => write!(f, "adaptive"),
+            Difficulty::Duel => write!(f, "duel"),
+            Difficulty::Bot => write!(f, "bot"),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(difficulty_name: &str) -> Result<Self, Self::Err> {
+        match difficulty_name {
+            "basic" => Ok(Difficulty::Basic),
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "expert" => Ok(Difficulty::Expert),
+            "tuned" => Ok(Difficulty::Tuned),
+            "adaptive" => Ok(Difficulty::Adaptive),
+            "duel" => Ok(Difficulty::Duel),
+            "bot" => Ok(Difficulty::Bot),
+            _ => Err(format!("\"{}\" isn't a difficulty, pick \"basic\", \"easy\", \"medium\", \"hard\", \"expert\", \"tuned\", \"adaptive\", \"duel\", or \"bot\".", difficulty_name)),
+        }
+    }
+}
+
+/// Plays the first card it can, drawing until it has one. It won't make a teammate draw if it can
+/// help it, and it picks the wild color a teammate asked for.
+#[derive(Default)]
+pub struct BasicStrategy;
+
+impl Strategy for BasicStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let playable_card_indexes = view.playable_card_indexes();
+        let Some(&first_playable_index) = playable_card_indexes.first() else {
+            return explained_draw_or_pass(view);
+        };
+        // Rather not make a teammate draw cards if there's something else to play.
+        let card_index = if view.pending_draw == 0 {
+            playable_card_indexes.iter().copied()
+                .find(|card_index| !hits_teammate(view, &view.hand[*card_index]))
+                .unwrap_or(first_playable_index)
+        } else {
+            first_playable_index
+        };
+        let action = play(view, card_index);
+        (action, Some(explain(view, &action, "It plays the first card it can, unless that would make a teammate draw.", Vec::new())))
+    }
+}
+
+/// Describes `action` for the player whose view it is, like "play a red 5".
+pub fn describe_action(view: &PlayerView, action: &Action) -> String {
+    match *action {
+        Action::Play { card_index, color, swap_with } => {
+            let mut description = format!("play a {}", view.hand[card_index]);
+            if let Some(color) = color.filter(|_| view.hand[card_index].wild) {
+                description += &format!(" as {}", color);
+            }
+            if let Some(swap_with) = swap_with {
+                description += &format!(" and swap hands with Player {}", swap_with + 1);
+            }
+            description
+        }
+        Action::Draw if view.pending_draw > 0 => format!("draw {} cards", view.pending_draw),
+        Action::Draw => "draw a card".to_string(),
+        Action::Pass => "pass".to_string(),
+        Action::CallUno => "call Uno".to_string(),
+        Action::Challenge(target) => format!("catch Player {} not calling Uno", target + 1),
+        Action::Signal(color) => format!("signal {} to their team", color),
+    }
+}
+
+/// Puts together why `action` was made, starting from `reason`. Why wilds got their colors, who
+/// got swapped with, and why teams got signaled are the same for every strategy, so they're
+/// added here. `scores` are the moves that were weighed up.
+fn explain(view: &PlayerView, action: &Action, reason: &str, scores: Vec<(Action, f64)>) -> Explanation {
+    let mut reasons = vec![reason.to_string()];
+    match *action {
+        Action::Play { card_index, color, swap_with } => {
+            if let Some(color) = color.filter(|_| view.hand[card_index].wild) {
+                reasons.push(wild_color_reason(view, card_index, color));
+            }
+            if let Some(swap_with) = swap_with {
+                reasons.push(format!("Player {} has {} cards, the fewest of anyone it could swap with.", swap_with + 1, view.hand_sizes[swap_with]));
+            }
+        }
+        Action::Signal(color) => {
+            reasons.push(format!("It's almost out of cards and the ones it has left are {}, so it's letting its team know.", color));
+        }
+        _ => {}
+    }
+    Explanation {
+        action: describe_action(view, action),
+        reasons,
+        scores: scores.iter().map(|(action, score)| (describe_action(view, action), *score)).collect(),
+    }
+}
+
+/// Why the wild at `card_index` was made `color`.
+fn wild_color_reason(view: &PlayerView, card_index: usize, color: Color) -> String {
+    if view.teammate_signal() == Some(color) {
+        return format!("It picked {} because a teammate signaled for it.", color);
+    }
+    let held = view.hand.iter().enumerate()
+        .filter(|(index, card)| *index != card_index && card.color == Some(color))
+        .count();
+    let lacked = colors_opponents_recently_lacked(view).into_iter().filter(|lacked_color| *lacked_color == color).count();
+    match (held, lacked) {
+        (0, 0) => format!("It picked {}, though it has no more {} cards.", color, color),
+        (held, 0) => format!("It picked {} since it has {} more {} cards to follow with.", color, held, color),
+        (0, lacked) => format!("It picked {} since opponents drew rather than play on {} {} times lately.", color, color, lacked),
+        (held, lacked) => format!(
+            "It picked {} since it has {} more {} cards to follow with, and opponents drew rather than play on {} {} times lately.",
+            color, held, color, color, lacked,
+        ),
+    }
+}
+
+/// `call_uno_or_challenge` along with why.
+fn explained_call_uno_or_challenge(view: &PlayerView) -> Option<(Action, Option<Explanation>)> {
+    let action = call_uno_or_challenge(view)?;
+    let reason = match action {
+        Action::Challenge(_) => "Catching an opponent who didn't call Uno makes them draw, and it can't hurt.",
+        _ => "It's about to be down to one card, and calling Uno first means nobody can catch it.",
+    };
+    Some((action, Some(explain(view, &action, reason, Vec::new()))))
+}
+
+/// `draw_or_pass` along with why.
+fn explained_draw_or_pass(view: &PlayerView) -> (Action, Option<Explanation>) {
+    let action = draw_or_pass(view);
+    let reason = match action {
+        Action::Pass => "It has nothing it can play, and there's nothing left to draw.",
+        _ if view.pending_draw > 0 => "It has no draw card to stack, so it has to take the penalty.",
+        _ => "It has nothing it can play.",
+    };
+    (action, Some(explain(view, &action, reason, Vec::new())))
+}
+
+/// Draws a card, or passes if there's nothing left to draw.
+fn draw_or_pass(view: &PlayerView) -> Action {
+    if view.can_draw() { Action::Draw } else { Action::Pass }
+}
+
+/// Catches an opponent who didn't call Uno, or calls Uno when down to two cards. Neither can hurt,
+/// so computer players do them before anything else.
+fn call_uno_or_challenge(view: &PlayerView) -> Option<Action> {
+    let caught_opponent = (0..view.missed_uno_calls.len())
+        .find(|player| view.missed_uno_calls[*player] && *player != view.seat && !view.is_teammate(*player));
+    if let Some(opponent) = caught_opponent {
+        return Some(Action::Challenge(opponent));
+    }
+    (view.hand.len() == 2 && !view.called_uno).then_some(Action::CallUno)
+}
+
+/// Plays the card at `card_index`, picking a wild color and who to swap hands with. Signals the
+/// player's team first if they're about to be down to the last couple cards of a color.
+fn play(view: &PlayerView, card_index: usize) -> Action {
+    play_with_wild_color(view, card_index, |rest_of_hand| choose_wild_color(rest_of_hand, view))
+}
+
+/// Like `play`, but wild colors come from `choose_color`, which is given the rest of the hand.
+/// A teammate's signal still wins out.
+fn play_with_wild_color(view: &PlayerView, card_index: usize, choose_color: impl FnOnce(&[Card]) -> Color) -> Action {
+    let card = view.hand[card_index];
+    let rest_of_hand: Vec<Card> = view.hand.iter().enumerate()
+        .filter(|(index, _)| *index != card_index)
+        .map(|(_, card)| *card)
+        .collect();
+
+    // Let teammates know which color to keep going when down to the last couple cards.
+    if view.rules.team_signals && view.teams[view.seat].is_some() && !view.signaled_this_turn && (1..=2).contains(&rest_of_hand.len()) {
+        if let Some(last_color) = rest_of_hand[0].color {
+            if view.signals[view.seat] != Some(last_color) && rest_of_hand.iter().all(|card| card.color == Some(last_color)) {
+                return Action::Signal(last_color);
+            }
+        }
+    }
+
+    // Help out a teammate who's signaled the color they want.
+    let color = card.wild.then(|| view.teammate_signal().unwrap_or_else(|| choose_color(&rest_of_hand)));
+    // Swap for the smallest hand, unless that would hand a teammate a worse one.
+    let swap_with = (0..view.hand_sizes.len())
+        .filter(|player| *player != view.seat && view.statuses[*player] == PlayerStatus::Playing)
+        .filter(|_| view.rules.card_effects(&card).contains(&Effect::SwapHands))
+        .min_by_key(|player| (view.is_teammate(*player), view.hand_sizes[*player]));
+    Action::Play { card_index, color, swap_with }
+}
+
+/// Whether playing `card` would make a teammate draw.
+fn hits_teammate(view: &PlayerView, card: &Card) -> bool {
+    card.draw_effect.is_some() && view.is_teammate(view.next_player_after(card))
+}
+
+/// Picks the wild color that's best for a player holding `hand`, which is their hand without the
+/// wild. Every card of a color makes it more likely they can keep playing, and action cards count
+/// double since they're the ones worth saving a turn for. Colors opponents recently had to draw on
+/// count extra, since those opponents probably can't follow them.
+pub fn choose_wild_color(hand: &[Card], view: &PlayerView) -> Color {
+    let colors_opponents_lack = colors_opponents_recently_lacked(view);
+    let score = |color: Color| -> i32 {
+        let hand_score: i32 = hand.iter()
+            .filter(|card| card.color == Some(color))
+            .map(|card| if card.number.is_some() { 1 } else { 2 })
+            .sum();
+        let opponents_lacking = colors_opponents_lack.iter().filter(|lacked_color| **lacked_color == color).count() as i32;
+        hand_score * 2 + opponents_lacking
+    };
+    // Ties go to whichever color comes first.
+    Color::for_side(view.side).iter().copied()
+        .fold(None, |best: Option<(Color, i32)>, color| match best {
+            Some((_, best_score)) if best_score >= score(color) => best,
+            _ => Some((color, score(color))),
+        })
+        .unwrap()
+        .0
+}
+
+/// The colors that were on top when an opponent chose to draw since this player last played, one
+/// for each time it happened.
+fn colors_opponents_recently_lacked(view: &PlayerView) -> Vec<Color> {
+    let since = view.history.iter()
+        .rposition(|event| matches!(event, Event::Played { player, .. } if *player == view.seat))
+        .map_or(0, |index| index + 1);
+    let mut top_color: Option<Color> = None;
+    let mut lacked_colors = Vec::new();
+    for (index, event) in view.history.iter().enumerate() {
+        match *event {
+            Event::Played { card, .. } => top_color = card.color,
+            Event::Drew { player, forced: false, .. } if index >= since && player != view.seat && !view.is_teammate(player) => {
+                lacked_colors.extend(top_color.filter(|color| color.side() == view.side));
+            }
+            _ => {}
+        }
+    }
+    lacked_colors
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::{Card, Side},
+        game::{Event, PlayerStatus, Rules, TurnOrder},
+        view::PlayerView,
+    };
+
+    /// What the player in seat 0 of a three player game sees, holding `hand`.
+    fn view<'a>(hand: &'a [Card], history: &'a [Event]) -> PlayerView<'a> {
+        PlayerView {
+            seat: 0,
+            current_player: 0,
+            hand: hand.to_vec(),
+            backs: vec![Vec::new(); 3],
+            top_card: None,
+            hand_sizes: vec![hand.len(), 7, 7],
+            statuses: vec![PlayerStatus::Playing; 3],
+            teams: vec![None; 3],
+            signals: vec![None; 3],
+            signaled_this_turn: false,
+            drew_this_turn: false,
+            called_uno: false,
+            missed_uno_calls: vec![false; 3],
+            turn_order: TurnOrder::Forward,
+            next_player: 1,
+            previous_player: 2,
+            pending_draw: 0,
+            deck_size: 80,
+            discard_size: 1,
+            side: Side::Light,
+            rules: Rules::default(),
+            history,
+            profiles: &[],
+        }
+    }
+
+    mod explain {
+        use super::{super::*, view};
+
+        #[test]
+        fn says_why_a_wild_got_its_color() {
+            let hand = [Card::from("wild"), Card::from("blue 2"), Card::from("blue 3")];
+            let top_card = Card::from("red 7");
+            let mut view = view(&hand, &[]);
+            view.top_card = Some(top_card);
+
+            let (action, explanation) = HeuristicStrategy.choose_explained_action(&view);
+            let explanation = explanation.unwrap();
+
+            assert_eq!(action, Action::Play { card_index: 0, color: Some(Color::Blue), swap_with: None });
+            assert_eq!(explanation.action, "play a wild as blue");
+            assert!(explanation.reasons.iter().any(|reason| reason.contains("2 more blue cards")));
+        }
+
+        #[test]
+        fn lists_the_moves_that_were_weighed_up() {
+            let hand = [Card::from("red 1"), Card::from("red 9"), Card::from("blue 2")];
+
+            let (_, explanation) = HeuristicStrategy.choose_explained_action(&view(&hand, &[]));
+            let explanation = explanation.unwrap();
+
+            assert_eq!(explanation.scores.len(), 3);
+            let (best, _) = explanation.scores.iter().max_by(|(_, a), (_, b)| a.total_cmp(b)).unwrap();
+            assert_eq!(*best, explanation.action);
+        }
+    }
+
+    mod strategy {
+        use super::super::*;
+
+        #[test]
+        fn bots_need_a_command_to_run() {
+            assert!(Difficulty::Bot.strategy().is_err());
+            assert!(Difficulty::Medium.strategy().is_ok());
+        }
+    }
+
+    mod describe_action {
+        use super::{super::*, view};
+
+        #[test]
+        fn describes_every_kind_of_action() {
+            let hand = [Card::from("red 5")];
+            let view = view(&hand, &[]);
+
+            assert_eq!(describe_action(&view, &Action::Play { card_index: 0, color: None, swap_with: None }), "play a red 5");
+            assert_eq!(describe_action(&view, &Action::Draw), "draw a card");
+            assert_eq!(describe_action(&view, &Action::Challenge(2)), "catch Player 3 not calling Uno");
+        }
+    }
+
+    mod choose_wild_color {
+        use super::{super::*, view};
+        use crate::card::Side;
+
+        #[test]
+        fn picks_the_color_with_the_most_cards() {
+            let hand = [Card::from("red 1"), Card::from("blue 2"), Card::from("blue 3"), Card::from("green 4")];
+
+            let color = choose_wild_color(&hand, &view(&hand, &[]));
+
+            assert_eq!(color, Color::Blue);
+        }
+
+        #[test]
+        fn action_cards_count_for_more() {
+            let hand = [Card::from("red skip"), Card::from("red draw2"), Card::from("blue 2"), Card::from("blue 3"), Card::from("blue 4")];
+
+            let color = choose_wild_color(&hand, &view(&hand, &[]));
+
+            assert_eq!(color, Color::Red);
+        }
+
+        #[test]
+        fn breaks_ties_with_colors_opponents_had_to_draw_on() {
+            let hand = [Card::from("red 1"), Card::from("green 4")];
+            let history = [
+                Event::Played { player: 0, card: Card::from("yellow 5") },
+                Event::Played { player: 1, card: Card::from("green 5") },
+                Event::Drew { player: 2, count: 1, forced: false },
+            ];
+
+            let color = choose_wild_color(&hand, &view(&hand, &history));
+
+            assert_eq!(color, Color::Green);
+        }
+
+        #[test]
+        fn forced_draws_dont_say_anything_about_a_hand() {
+            let hand = [Card::from("red 1"), Card::from("green 4")];
+            let history = [
+                Event::Played { player: 1, card: Card::from("green draw2") },
+                Event::Drew { player: 2, count: 2, forced: true },
+            ];
+
+            let color = choose_wild_color(&hand, &view(&hand, &history));
+
+            assert_eq!(color, Color::Red);
+        }
+
+        #[test]
+        fn picks_from_the_side_thats_showing() {
+            let hand = [Card::from("red 1 / teal 1"), Card::from("teal 2")];
+            let mut view = view(&hand, &[]);
+            view.side = Side::Dark;
+
+            let color = choose_wild_color(&hand, &view);
+
+            assert_eq!(color.side(), Side::Dark);
+        }
+    }
+}
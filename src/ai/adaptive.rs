@@ -0,0 +1,235 @@
+//! Computer players that learn how each human plays.
+
+use crate::{
+    ai::{
+        choose_wild_color, explain, explained_call_uno_or_challenge, explained_draw_or_pass,
+        heuristic::{score_card, scored_plays},
+        play_with_wild_color, Explanation, Strategy,
+    },
+    card::{Card, Color, Effect},
+    game::Action,
+    profile::Profile,
+    view::PlayerView,
+};
+
+/// Points a card gets for each bit more often than usual the next player draws on its color.
+const SHORT_ON_POINTS: f64 = 100.0;
+/// Cards in hand a wild color is worth for each bit more often than usual the next player draws
+/// on it.
+const SHORT_ON_CARDS: f64 = 10.0;
+/// Points a card that skips or draws on an opponent about to go out gets for each bit more likely
+/// they are to call Uno. Players who forget to call it tend to get caught and draw anyway.
+const RELIABLE_CALLER_POINTS: f64 = 200.0;
+/// How far from usual a tendency has to be before it's worth mentioning.
+const NOTICEABLE: f64 = 0.05;
+
+/// Plays like a medium computer player, but remembers how each human tends to play from game to
+/// game. It sticks to colors the next player is usually short on, unless they tend to be holding a
+/// wild anyway, and goes after players about to go out harder when they're good about calling Uno.
+#[derive(Default)]
+pub struct AdaptiveStrategy;
+
+impl Strategy for AdaptiveStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let scores: Vec<(usize, i32)> = view.playable_card_indexes().into_iter()
+            .map(|card_index| {
+                let card = &view.hand[card_index];
+                (card_index, score_card(view, card) + exploit_score(view, card))
+            })
+            .collect();
+        let Some(&(card_index, _)) = scores.iter().max_by_key(|(_, score)| *score) else {
+            return explained_draw_or_pass(view);
+        };
+        let next_player = view.next_player_after(&view.hand[card_index]);
+        let action = play_with_wild_color(view, card_index, |rest_of_hand| choose_color_against(view, rest_of_hand, next_player));
+        let reason = "It scored each card it could play like a medium player does, plus how it'd suit the way the next player tends to play.";
+        let mut explanation = explain(view, &action, reason, scored_plays(&scores));
+        explanation.reasons.extend(tendency_reasons(view, &action, next_player));
+        (action, Some(explanation))
+    }
+}
+
+/// How `player` played in earlier games, if they're an opponent who's been seen before.
+fn opponent_profile<'a>(view: &PlayerView<'a>, player: usize) -> Option<&'a Profile> {
+    if player == view.seat || view.is_teammate(player) {
+        return None;
+    }
+    view.profiles.get(player)
+}
+
+/// How likely `profile`'s player is to be short on `color`, counting that a wild gets them out
+/// of any color.
+fn shortness(profile: &Profile, color: Color) -> f64 {
+    profile.short_on(color) * (1.0 - profile.holding_wilds.rate(0.3))
+}
+
+/// Extra points for playing `card`, from how whoever goes after it tends to play.
+fn exploit_score(view: &PlayerView, card: &Card) -> i32 {
+    let next_player = view.next_player_after(card);
+    let Some(profile) = opponent_profile(view, next_player) else {
+        return 0;
+    };
+    let mut score = 0.0;
+    // Wilds get their color picked once they're played.
+    if let Some(color) = card.color.filter(|_| !card.wild) {
+        score += SHORT_ON_POINTS * shortness(profile, color);
+    }
+    if hurts_player_close_to_uno(view, card, next_player) {
+        score += RELIABLE_CALLER_POINTS * (profile.uno_calls.rate(0.5) - 0.5);
+    }
+    score.round() as i32
+}
+
+/// Whether `card` skips or draws on `next_player` when they're about to go out.
+fn hurts_player_close_to_uno(view: &PlayerView, card: &Card, next_player: usize) -> bool {
+    let hurts_next_player = view.rules.card_effects(card).iter().any(|effect| {
+        matches!(effect, Effect::Skip | Effect::SkipEveryone | Effect::Draw(_) | Effect::DrawColor)
+    });
+    hurts_next_player && view.hand_sizes[next_player] <= 2
+}
+
+/// Picks a wild color from what's left in hand, like `choose_wild_color`, but leaning towards
+/// colors `next_player` tends to be short on.
+fn choose_color_against(view: &PlayerView, rest_of_hand: &[Card], next_player: usize) -> Color {
+    let Some(profile) = opponent_profile(view, next_player) else {
+        return choose_wild_color(rest_of_hand, view);
+    };
+    let score = |color: Color| -> f64 {
+        let held: usize = rest_of_hand.iter()
+            .filter(|card| card.color == Some(color))
+            .map(|card| if card.number.is_some() { 1 } else { 2 })
+            .sum();
+        held as f64 + SHORT_ON_CARDS * shortness(profile, color)
+    };
+    // Ties go to whichever color comes first.
+    Color::for_side(view.side).into_iter()
+        .fold(None, |best: Option<(Color, f64)>, color| match best {
+            Some((_, best_score)) if best_score >= score(color) => best,
+            _ => Some((color, score(color))),
+        })
+        .unwrap()
+        .0
+}
+
+/// What about `next_player`'s habits went into `action`.
+fn tendency_reasons(view: &PlayerView, action: &Action, next_player: usize) -> Vec<String> {
+    let Action::Play { card_index, color, .. } = *action else {
+        return Vec::new();
+    };
+    let Some(profile) = opponent_profile(view, next_player) else {
+        return Vec::new();
+    };
+    let card = view.hand[card_index];
+    let mut reasons = Vec::new();
+    if let Some(color) = color.or(card.color) {
+        if shortness(profile, color) > NOTICEABLE {
+            reasons.push(format!("Player {} has drawn on {} more often than on other colors in earlier games.", next_player + 1, color));
+        }
+    }
+    if hurts_player_close_to_uno(view, &card, next_player) && profile.uno_calls.out_of > 0 {
+        let calls = profile.uno_calls.rate(0.5);
+        if calls > 0.5 + NOTICEABLE {
+            reasons.push(format!("Player {} usually remembers to call Uno, so it can't count on them getting caught.", next_player + 1));
+        } else if calls < 0.5 - NOTICEABLE {
+            reasons.push(format!("Player {} often forgets to call Uno, so they'll likely get caught anyway.", next_player + 1));
+        }
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::{Card, Color, Side},
+        game::{PlayerStatus, Rules, TurnOrder},
+        profile::Profile,
+        view::PlayerView,
+    };
+
+    /// What the player in seat 0 of a two player game sees, holding `hand` with `top_card` on
+    /// the discard pile.
+    fn view<'a>(hand: &'a [Card], top_card: &'a Card, profiles: &'a [Profile]) -> PlayerView<'a> {
+        PlayerView {
+            seat: 0,
+            current_player: 0,
+            hand: hand.to_vec(),
+            backs: vec![Vec::new(); 2],
+            top_card: Some(*top_card),
+            hand_sizes: vec![hand.len(), 7],
+            statuses: vec![PlayerStatus::Playing; 2],
+            teams: vec![None; 2],
+            signals: vec![None; 2],
+            signaled_this_turn: false,
+            drew_this_turn: false,
+            called_uno: false,
+            missed_uno_calls: vec![false; 2],
+            turn_order: TurnOrder::Forward,
+            next_player: 1,
+            previous_player: 1,
+            pending_draw: 0,
+            deck_size: 80,
+            discard_size: 1,
+            side: Side::Light,
+            rules: Rules::default(),
+            history: &[],
+            profiles,
+        }
+    }
+
+    /// Someone who never holds wilds and always draws on blue, but never on anything else.
+    fn short_on_blue() -> Profile {
+        let mut profile = Profile::default();
+        for _ in 0..20 {
+            profile.holding_wilds.record(false);
+            profile.draws_on_mut(Color::Blue).record(true);
+            profile.draws_on_mut(Color::Red).record(false);
+            profile.draws_on_mut(Color::Green).record(false);
+        }
+        profile
+    }
+
+    mod adaptive_strategy {
+        use super::{super::*, short_on_blue, view};
+
+        #[test]
+        fn picks_wild_colors_the_next_player_is_short_on() {
+            let hand = [Card::from("wild"), Card::from("green 2"), Card::from("yellow 3")];
+            let top_card = Card::from("red 7");
+            let profiles = [Profile::default(), short_on_blue()];
+
+            let (action, explanation) = AdaptiveStrategy.choose_explained_action(&view(&hand, &top_card, &profiles));
+
+            assert!(matches!(action, Action::Play { card_index: 0, color: Some(Color::Blue), .. }), "{:?}", action);
+            assert!(explanation.unwrap().reasons.iter().any(|reason| reason.contains("drawn on blue")));
+        }
+
+        #[test]
+        fn plays_into_colors_the_next_player_is_short_on() {
+            // A medium player would get rid of the red 9 first, since it's worth more points.
+            let hand = [Card::from("red 9"), Card::from("blue 7"), Card::from("green 1")];
+            let top_card = Card::from("red 7");
+            let profiles = [Profile::default(), short_on_blue()];
+
+            let action = AdaptiveStrategy.choose_action(&view(&hand, &top_card, &profiles));
+
+            assert!(matches!(action, Action::Play { card_index: 1, .. }), "{:?}", action);
+        }
+
+        #[test]
+        fn plays_like_a_medium_player_against_strangers() {
+            let hand = [Card::from("wild"), Card::from("green 2"), Card::from("green 3")];
+            let top_card = Card::from("red 7");
+
+            let action = AdaptiveStrategy.choose_action(&view(&hand, &top_card, &[]));
+
+            assert!(matches!(action, Action::Play { card_index: 0, color: Some(Color::Green), .. }), "{:?}", action);
+        }
+    }
+}
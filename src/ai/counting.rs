@@ -0,0 +1,209 @@
+//! Hard computer players.
+
+use crate::{
+    ai::{
+        explain, explained_call_uno_or_challenge, explained_draw_or_pass,
+        heuristic::{score_card, scored_plays},
+        play_with_wild_color, Explanation, Strategy,
+    },
+    card::{Card, Color, Effect, Side},
+    game::{can_play_card, Action, Event},
+    view::PlayerView,
+};
+
+/// Plays like a medium player, but keeps count of the cards that have been played to work out
+/// what's left in the deck and what opponents are likely holding. Cards and wild colors the next
+/// opponent probably can't follow get played first.
+#[derive(Default)]
+pub struct CountingStrategy {
+    /// The cards this player last gave away by swapping hands.
+    handed_over: Option<HandedOver>,
+}
+
+/// Cards a player knows someone else is holding because they gave them to them.
+pub(super) struct HandedOver {
+    player: usize,
+    cards: Vec<Card>,
+    /// Where in the history the swap happened.
+    history_index: usize,
+}
+
+impl Strategy for CountingStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let count = CardCount::new(view, self.handed_over.as_ref());
+        let scores: Vec<(usize, i32)> = view.playable_card_indexes().into_iter()
+            .map(|card_index| (card_index, score_card(view, &view.hand[card_index]) + count.follow_score(view, card_index)))
+            .collect();
+        let Some(&(card_index, _)) = scores.iter().max_by_key(|(_, score)| *score) else {
+            return explained_draw_or_pass(view);
+        };
+        let next_player = view.next_player_after(&view.hand[card_index]);
+        let action = play_with_wild_color(view, card_index, |rest_of_hand| count.choose_wild_color(view, rest_of_hand, next_player));
+
+        if let Action::Play { swap_with: Some(swap_with), .. } = action {
+            if view.rules.card_effects(&view.hand[card_index]).contains(&Effect::SwapHands) {
+                self.handed_over = Some(HandedOver {
+                    player: swap_with,
+                    cards: view.hand.iter().enumerate().filter(|(index, _)| *index != card_index).map(|(_, card)| *card).collect(),
+                    history_index: view.history.len(),
+                });
+            }
+        }
+        let reason = "It kept count of the cards played, and scored each card it could play higher the less likely the next opponent is to follow it.";
+        (action, Some(explain(view, &action, reason, scored_plays(&scores))))
+    }
+}
+
+/// What a player can work out about the cards they can't see.
+pub(super) struct CardCount {
+    /// Cards that could still be in the deck or in someone else's hand, with the side that's up
+    /// showing. After the discard pile is shuffled back into the deck this undercounts, so it's
+    /// only good for comparing how likely cards are.
+    pub(super) unseen: Vec<Card>,
+    /// The colors each player seems to be out of, because they drew instead of following them.
+    pub(super) lacking: Vec<Vec<Color>>,
+    /// Cards each player is known to be holding.
+    known: Vec<Vec<Card>>,
+    /// This player's hand with a guess at the backs they can't see in Uno Flip, taken out of
+    /// `unseen`.
+    pub(super) hand: Vec<Card>,
+}
+
+impl CardCount {
+    pub(super) fn new(view: &PlayerView, handed_over: Option<&HandedOver>) -> CardCount {
+        let player_count = view.hand_sizes.len();
+        let mut lacking: Vec<Vec<Color>> = vec![Vec::new(); player_count];
+        let mut known: Vec<Vec<Card>> = vec![Vec::new(); player_count];
+        let mut seen: Vec<Card> = Vec::new();
+        let mut top_color: Option<Color> = None;
+
+        for (index, event) in view.history.iter().enumerate() {
+            match *event {
+                Event::Played { player, card } => {
+                    seen.push(card);
+                    take_card(&mut known[player], &card);
+                    lacking[player].retain(|color| Some(*color) != card.color);
+                    top_color = card.color;
+                    // Once hands change owners nothing's known about who holds what.
+                    let effects = view.rules.card_effects(&card);
+                    if effects.contains(&Effect::SwapHands) || effects.contains(&Effect::ShuffleHands) {
+                        lacking.iter_mut().for_each(Vec::clear);
+                        known.iter_mut().for_each(Vec::clear);
+                    }
+                    if let Some(handed_over) = handed_over.filter(|handed_over| handed_over.history_index == index) {
+                        known[handed_over.player] = handed_over.cards.clone();
+                    }
+                }
+                Event::Discarded { player, card } => {
+                    seen.push(card);
+                    take_card(&mut known[player], &card);
+                }
+                Event::Drew { player, forced: false, .. } => {
+                    if let Some(top_color) = top_color {
+                        if !lacking[player].contains(&top_color) {
+                            lacking[player].push(top_color);
+                        }
+                    }
+                }
+                // A pile of cards they didn't pick could have any color in it.
+                Event::Drew { player, forced: true, .. } => lacking[player].clear(),
+                Event::Challenged { .. } | Event::Skipped { .. } => {}
+            }
+        }
+
+        let mut unseen: Vec<Card> = view.rules.mode.deck();
+        if view.side == Side::Dark {
+            unseen.iter_mut().for_each(Card::flip);
+        }
+        for card in &seen {
+            take_card(&mut unseen, card);
+        }
+        // Cards in hand go last, since without their backs they could be any of a few cards.
+        let hand = view.hand.iter().map(|card| take_card(&mut unseen, card).unwrap_or(*card)).collect();
+        for card in known.iter().flatten() {
+            take_card(&mut unseen, card);
+        }
+
+        CardCount { unseen, lacking, known, hand }
+    }
+
+    /// The chance `player` has a card they can play on `top_card`.
+    fn chance_to_follow(&self, view: &PlayerView, player: usize, top_card: &Card) -> f64 {
+        if self.known[player].iter().any(|card| can_play_card(Some(top_card), card)) {
+            return 1.0;
+        }
+        let unknown_cards = view.hand_sizes[player].saturating_sub(self.known[player].len());
+        if self.unseen.is_empty() || unknown_cards == 0 {
+            return 0.0;
+        }
+        let followers = self.unseen.iter()
+            .filter(|card| can_play_card(Some(top_card), card))
+            .filter(|card| card.color.is_none_or(|color| !self.lacking[player].contains(&color)))
+            .count();
+        let chance_per_card = followers as f64 / self.unseen.len() as f64;
+        1.0 - (1.0 - chance_per_card).powi(unknown_cards as i32)
+    }
+
+    /// How much it'd help for `top_card` to be on top when it's `player`'s turn. Opponents should
+    /// be stuck, teammates shouldn't.
+    fn denial_score(&self, view: &PlayerView, player: usize, top_card: &Card) -> i32 {
+        let chance_to_follow = self.chance_to_follow(view, player, top_card);
+        let denial = if view.is_teammate(player) { chance_to_follow } else { 1.0 - chance_to_follow };
+        (30.0 * denial).round() as i32
+    }
+
+    /// The extra worth of playing the card at `card_index` from how likely the next player is to
+    /// follow it. Cards that skip or draw on the next player don't let them follow at all, so
+    /// there's nothing to add.
+    fn follow_score(&self, view: &PlayerView, card_index: usize) -> i32 {
+        let mut card = view.hand[card_index];
+        let effects = view.rules.card_effects(&card);
+        if effects.iter().any(|effect| matches!(effect, Effect::Skip | Effect::SkipEveryone | Effect::Draw(_) | Effect::DrawColor)) {
+            return 0;
+        }
+        let next_player = view.next_player_after(&card);
+        if card.wild {
+            let rest_of_hand: Vec<Card> = view.hand.iter().enumerate()
+                .filter(|(index, _)| *index != card_index)
+                .map(|(_, card)| *card)
+                .collect();
+            card.color = Some(self.choose_wild_color(view, &rest_of_hand, next_player));
+        }
+        self.denial_score(view, next_player, &card)
+    }
+
+    /// Picks the wild color with the most cards left to play in `hand`, like medium players do,
+    /// but also counts how unlikely `next_player` is to follow it.
+    fn choose_wild_color(&self, view: &PlayerView, hand: &[Card], next_player: usize) -> Color {
+        let score = |color: Color| -> i32 {
+            let hand_score: i32 = hand.iter()
+                .filter(|card| card.color == Some(color))
+                .map(|card| if card.number.is_some() { 1 } else { 2 })
+                .sum();
+            let colored_wild = Card { color: Some(color), wild: true, ..Card::default() };
+            hand_score * 10 + self.denial_score(view, next_player, &colored_wild)
+        };
+        // Ties go to whichever color comes first.
+        Color::for_side(view.side).iter().copied()
+            .fold(None, |best: Option<(Color, i32)>, color| match best {
+                Some((_, best_score)) if best_score >= score(color) => best,
+                _ => Some((color, score(color))),
+            })
+            .unwrap()
+            .0
+    }
+}
+
+/// Takes one copy of `card` out of `cards`, if it's there.
+pub(super) fn take_card(cards: &mut Vec<Card>, card: &Card) -> Option<Card> {
+    cards.iter()
+        .position(|other_card| other_card.is_same_card_as(card))
+        .map(|index| cards.swap_remove(index))
+}
@@ -0,0 +1,264 @@
+//! Computer players for two player games.
+
+use std::time::{Duration, Instant};
+
+use rand::thread_rng;
+
+use crate::{
+    ai::{
+        explain, explained_call_uno_or_challenge,
+        heuristic::score_card,
+        mcts::{legal_moves, Dealer},
+        Explanation, HeuristicStrategy, Strategy,
+    },
+    game::{Action, Uno},
+    view::PlayerView,
+};
+
+/// How many guesses at the opponent's hand get searched through.
+const DEALS: usize = 6;
+/// What winning and losing are worth. Everything else is somewhere in between.
+const WIN: f64 = 1.0;
+const LOSS: f64 = -1.0;
+/// How much having fewer cards than the opponent can be worth, short of actually winning.
+const HAND_SIZE_WEIGHT: f64 = 0.9;
+/// How many cards ahead a player has to be before they're well on the way to winning.
+const LEAD_SCALE: f64 = 3.0;
+/// How much a medium player's score for a card counts towards playing it.
+const TIEBREAK_WEIGHT: f64 = 0.0002;
+/// How much less a wild counts than other cards, when counting cards left to get rid of.
+const WILD_DISCOUNT: f64 = 0.5;
+
+/// Searches a few moves ahead with expectiminimax: it makes the moves that are best for it, the
+/// opponent makes the moves that are worst for it, and draws are weighed up over every card that
+/// could turn up, by how many of each are thought to be left. The opponent's hand is guessed a few
+/// different ways and every guess gets searched. Searches go one move deeper at a time until
+/// `depth` or `time_limit` is reached, and branches that can't change the outcome get cut off.
+/// Only two player games get searched, bigger ones get played like a medium player would.
+pub struct ExpectiminimaxStrategy {
+    /// The most moves to look ahead, counting both players' moves and draws.
+    pub depth: usize,
+    /// The longest to search per move, however deep it's gotten.
+    pub time_limit: Duration,
+}
+
+impl Default for ExpectiminimaxStrategy {
+    fn default() -> Self {
+        ExpectiminimaxStrategy {
+            depth: 4,
+            time_limit: Duration::from_millis(250),
+        }
+    }
+}
+
+impl Strategy for ExpectiminimaxStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        if view.hand_sizes.len() != 2 {
+            return HeuristicStrategy.choose_explained_action(view);
+        }
+        let dealer = Dealer::new(view);
+        let mut rng = thread_rng();
+        let deals: Vec<Uno> = (0..DEALS).map(|_| dealer.deal(view, &mut rng)).collect();
+        // The player's own cards are dealt out in the same order in every deal, so the moves are
+        // the same in all of them.
+        let moves: Vec<Action> = legal_moves(&deals[0]).into_iter().map(|(action, _)| action).collect();
+        if moves.len() <= 1 {
+            let action = moves.first().copied().unwrap_or(Action::Pass);
+            return (action, Some(explain(view, &action, "It was the only move there was to make.", Vec::new())));
+        }
+
+        let mut search = Search { player: view.seat, deadline: Instant::now() + self.time_limit };
+        let mut searched: Option<(usize, Vec<f64>)> = None;
+        for depth in 1..=self.depth {
+            match search.root(&deals, &moves, depth) {
+                Some(values) => searched = Some((depth, values)),
+                None => break,
+            }
+        }
+        let Some((depth, values)) = searched else {
+            return HeuristicStrategy.choose_explained_action(view);
+        };
+
+        // Searches often can't tell moves apart, so the way a medium player scores cards settles
+        // close calls.
+        let values: Vec<f64> = moves.iter().zip(values)
+            .map(|(action, value)| match *action {
+                Action::Play { card_index, .. } => value + TIEBREAK_WEIGHT * score_card(view, &view.hand[card_index]) as f64,
+                _ => value,
+            })
+            .collect();
+        let (best_move, _) = moves.iter().zip(&values)
+            .max_by(|(_, value), (_, other_value)| value.total_cmp(other_value))
+            .unwrap();
+        // Values go from a loss to a win, so scores are how good each move looked out of 100.
+        let scores = moves.iter().zip(&values)
+            .map(|(action, value)| (*action, 100.0 * (value - LOSS) / (WIN - LOSS)))
+            .collect();
+        let reason = format!(
+            "It looked {} moves ahead in {} guesses at its opponent's hand, weighing up every card a draw could turn up, and made the move that came out best. Scores are how good each move looked, out of 100.",
+            depth, DEALS,
+        );
+        (*best_move, Some(explain(view, best_move, &reason, scores)))
+    }
+}
+
+/// One search, for the player in `player`'s seat.
+struct Search {
+    player: usize,
+    /// Searches still going at this point get given up on.
+    deadline: Instant,
+}
+
+impl Search {
+    /// What each of `moves` is worth on average over `deals`, looking `depth` moves ahead. Gives
+    /// back nothing if it ran out of time.
+    fn root(&mut self, deals: &[Uno], moves: &[Action], depth: usize) -> Option<Vec<f64>> {
+        let mut totals = vec![0.0; moves.len()];
+        for deal in deals {
+            for (total, action) in totals.iter_mut().zip(moves) {
+                *total += self.after(deal, *action, depth - 1, LOSS, WIN)?;
+            }
+        }
+        Some(totals.into_iter().map(|total| total / deals.len() as f64).collect())
+    }
+
+    /// What `state` is worth looking `depth` moves ahead. Values at or below `alpha` or at or above
+    /// `beta` won't be used, so they only need to be bounds. Gives back nothing if it ran out of
+    /// time.
+    fn value(&mut self, state: &Uno, depth: usize, mut alpha: f64, mut beta: f64) -> Option<f64> {
+        if depth == 0 || state.game_over() {
+            return Some(self.evaluate(state));
+        }
+        if Instant::now() > self.deadline {
+            return None;
+        }
+        let moves = legal_moves(state);
+        if moves.is_empty() {
+            return Some(self.evaluate(state));
+        }
+        let maximizing = state.current_player() == self.player;
+        let mut best = if maximizing { LOSS } else { WIN };
+        for (action, _) in moves {
+            let value = self.after(state, action, depth - 1, alpha, beta)?;
+            if maximizing {
+                best = best.max(value);
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(value);
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+        Some(best)
+    }
+
+    /// What `state` is worth once `action` is made in it.
+    fn after(&mut self, state: &Uno, action: Action, depth: usize, alpha: f64, beta: f64) -> Option<f64> {
+        if action == Action::Draw && state.pending_draw() == 0 && !state.deck().is_empty() {
+            return self.chance(state, depth, alpha, beta);
+        }
+        let mut next = state.copy_position();
+        next.apply_action(next.current_player(), action).expect("legal actions can be applied");
+        self.value(&next, depth, alpha, beta)
+    }
+
+    /// What drawing a card in `state` is worth, averaged over every card that could be drawn. Once
+    /// the cards tried so far show the average has to land outside `alpha` to `beta`, the rest
+    /// aren't tried.
+    fn chance(&mut self, state: &Uno, depth: usize, alpha: f64, beta: f64) -> Option<f64> {
+        let deck = state.deck();
+        // Where each different card is in the deck, and how many copies of it there are.
+        let mut outcomes: Vec<(usize, usize)> = Vec::new();
+        for (deck_index, card) in deck.iter().enumerate() {
+            match outcomes.iter_mut().find(|(other_index, _)| deck[*other_index].is_same_card_as(card)) {
+                Some((_, copies)) => *copies += 1,
+                None => outcomes.push((deck_index, 1)),
+            }
+        }
+
+        let mut total = 0.0;
+        let mut chance_left = 1.0;
+        for (deck_index, copies) in outcomes {
+            let chance = copies as f64 / deck.len() as f64;
+            chance_left -= chance;
+            // The values this card could have that would still leave the average inside the window.
+            let card_alpha = ((alpha - total - chance_left * WIN) / chance).max(LOSS);
+            let card_beta = ((beta - total - chance_left * LOSS) / chance).min(WIN);
+            let mut next = state.copy_position();
+            next.deal_next(&[deck_index]);
+            next.apply_action(next.current_player(), Action::Draw).expect("drawing was legal");
+            total += chance * self.value(&next, depth, card_alpha, card_beta)?;
+            if total + chance_left * WIN <= alpha {
+                return Some(total + chance_left * WIN);
+            }
+            if total + chance_left * LOSS >= beta {
+                return Some(total + chance_left * LOSS);
+            }
+        }
+        Some(total)
+    }
+
+    /// How good `state` is for the player, from a loss to a win. Games that aren't over go by who
+    /// has fewer cards.
+    fn evaluate(&self, state: &Uno) -> f64 {
+        if let Some(winner) = state.winning_player_index() {
+            return if winner as usize == self.player { WIN } else { LOSS };
+        }
+        let opponent = 1 - self.player;
+        let lead = cards_to_go(state, opponent) - cards_to_go(state, self.player);
+        HAND_SIZE_WEIGHT * (lead / LEAD_SCALE).tanh()
+    }
+}
+
+/// About how many cards `player` still has to get rid of. Wilds can always be played, so they
+/// count for less, and whoever's turn it is has to draw if they can't play.
+fn cards_to_go(state: &Uno, player: usize) -> f64 {
+    let hand = state.hand(player);
+    let wilds = hand.iter().filter(|card| card.wild).count() as f64;
+    let mut cards = hand.len() as f64 - WILD_DISCOUNT * wilds;
+    if state.current_player() == player && !state.legal_actions(player).iter().any(|action| matches!(action, Action::Play { .. })) {
+        cards += 1.0;
+    }
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    mod expectiminimax_strategy {
+        use super::super::*;
+        fn quick() -> ExpectiminimaxStrategy {
+            ExpectiminimaxStrategy { depth: 2, time_limit: Duration::from_secs(5) }
+        }
+
+        #[test]
+        fn only_makes_legal_moves() {
+            let mut uno = Uno::new(0, 2);
+            for _ in 0..30 {
+                if uno.game_over() {
+                    break;
+                }
+                let seat = uno.current_player();
+                let action = quick().choose_action(&uno.view(seat));
+                assert!(uno.legal_actions(seat).contains(&action), "{:?} isn't legal", action);
+                uno.automate_current_player_turn();
+            }
+        }
+
+        #[test]
+        fn plays_like_a_medium_player_in_bigger_games() {
+            let uno = Uno::new(0, 3);
+            let view = uno.view(uno.current_player());
+
+            assert_eq!(quick().choose_action(&view), HeuristicStrategy.choose_action(&view));
+        }
+    }
+}
@@ -0,0 +1,237 @@
+//! Computer players run by other programs.
+//!
+//! Bots talk to the game one line of JSON at a time over their stdin and stdout. Each time it's
+//! their move they're sent an observation, which is everything their player can see plus the
+//! actions they're allowed to take:
+//!
+//! `{"type":"observation","seat":1,"hand":[...],...,"legal_actions":["draw",{"play":{...}}]}`
+//!
+//! and they answer with one of the legal actions, written the same way. An answer that can't be
+//! read or isn't allowed gets an error back, like `{"type":"error","message":"..."}`, and the bot
+//! is asked again.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::{
+    ai::{explain, BasicStrategy, Explanation, Strategy},
+    game::Action,
+    view::PlayerView,
+};
+
+/// How long bots get to answer unless they're given longer.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many unusable answers a bot can give for one move before a basic move is made for it.
+const MAX_ATTEMPTS: usize = 3;
+
+/// A line sent to a bot.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Message<'a> {
+    Observation {
+        #[serde(flatten)]
+        view: &'a PlayerView<'a>,
+        legal_actions: &'a [Action],
+    },
+    Error { message: String },
+}
+
+/// Plays whatever a bot program says to. Bots that take too long, stop answering, or keep asking
+/// for things the rules don't allow get a basic move made for them instead, so they can't hold up
+/// the game.
+pub struct ExternalStrategy {
+    to_bot: Box<dyn Write>,
+    /// Lines the bot has written, read on their own thread so the bot can be timed out.
+    from_bot: Receiver<String>,
+    timeout: Duration,
+    /// The bot's process, which gets stopped once the strategy is done with.
+    process: Option<Child>,
+}
+
+impl ExternalStrategy {
+    /// Starts `command` as a bot. The command is split on spaces into the program and its
+    /// arguments.
+    pub fn spawn(command: &str, timeout: Duration) -> Result<ExternalStrategy, String> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("The bot command is empty.")?;
+        let mut process = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|error| format!("Couldn't start the bot \"{}\": {}", command, error))?;
+        let to_bot = process.stdin.take().expect("the bot's stdin is piped");
+        let from_bot = BufReader::new(process.stdout.take().expect("the bot's stdout is piped"));
+        let mut strategy = ExternalStrategy::connect(to_bot, from_bot, timeout);
+        strategy.process = Some(process);
+        Ok(strategy)
+    }
+
+    /// Plays through a bot that reads from `to_bot` and writes to `from_bot`.
+    pub fn connect(to_bot: impl Write + 'static, from_bot: impl BufRead + Send + 'static, timeout: Duration) -> ExternalStrategy {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in from_bot.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        ExternalStrategy { to_bot: Box::new(to_bot), from_bot: receiver, timeout, process: None }
+    }
+
+    fn send(&mut self, message: &Message) -> std::io::Result<()> {
+        let line = serde_json::to_string(message).expect("messages can always be written as JSON");
+        writeln!(self.to_bot, "{}", line)?;
+        self.to_bot.flush()
+    }
+
+    /// Asks the bot what to do, giving it a few tries to come up with something allowed. Gives
+    /// up early if the bot is gone or runs out of time.
+    fn ask(&mut self, view: &PlayerView) -> Option<Action> {
+        // Answers that came in too late for an earlier move would be mistaken for this one's.
+        while self.from_bot.try_recv().is_ok() {}
+
+        let legal_actions = view.legal_actions();
+        self.send(&Message::Observation { view, legal_actions: &legal_actions }).ok()?;
+        for _ in 0..MAX_ATTEMPTS {
+            let answer = match self.from_bot.recv_timeout(self.timeout) {
+                Ok(answer) => answer,
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+            };
+            let message = match serde_json::from_str::<Action>(&answer) {
+                Ok(action) if legal_actions.contains(&action) => return Some(action),
+                Ok(_) => format!("{} isn't one of the legal actions.", answer),
+                Err(error) => format!("Couldn't read {} as an action: {}", answer, error),
+            };
+            self.send(&Message::Error { message }).ok()?;
+        }
+        None
+    }
+}
+
+impl Strategy for ExternalStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        match self.ask(view) {
+            Some(action) => (action, Some(explain(view, &action, "The bot program picked it.", Vec::new()))),
+            None => {
+                let action = BasicStrategy.choose_action(view);
+                let reason = "The bot program didn't come up with an allowed move in time, so it made a basic move instead.";
+                (action, Some(explain(view, &action, reason, Vec::new())))
+            }
+        }
+    }
+}
+
+impl Drop for ExternalStrategy {
+    fn drop(&mut self) {
+        if let Some(process) = &mut self.process {
+            // The bot may already be gone, which is fine.
+            let _ = process.kill();
+            let _ = process.wait();
+        }
+    }
+}
+
+/// A bot that always takes the first legal action it's offered. It shows what a bot has to do
+/// and is something to test against. `rust-uno echo-bot` runs it.
+pub fn echo_bot(input: impl BufRead, mut output: impl Write) -> Result<(), String> {
+    for line in input.lines() {
+        let line = line.map_err(|error| format!("Couldn't read from the game: {}", error))?;
+        let message: serde_json::Value = serde_json::from_str(&line)
+            .map_err(|error| format!("Couldn't read {} from the game: {}", line, error))?;
+        if message["type"] != "observation" {
+            continue;
+        }
+        writeln!(output, "{}", message["legal_actions"][0])
+            .and_then(|_| output.flush())
+            .map_err(|error| format!("Couldn't write to the game: {}", error))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    mod external_strategy {
+        use std::io::{pipe, PipeReader, PipeWriter};
+
+        use super::super::*;
+        use crate::game::Uno;
+
+        /// A strategy talking to a bot on the other ends of a couple pipes.
+        fn connect(timeout: Duration) -> (ExternalStrategy, BufReader<PipeReader>, PipeWriter) {
+            let (from_game, to_bot) = pipe().unwrap();
+            let (from_bot, to_game) = pipe().unwrap();
+            let strategy = ExternalStrategy::connect(to_bot, BufReader::new(from_bot), timeout);
+            (strategy, BufReader::new(from_game), to_game)
+        }
+
+        #[test]
+        fn plays_what_the_echo_bot_picks() {
+            let (mut strategy, from_game, to_game) = connect(DEFAULT_TIMEOUT);
+            thread::spawn(move || echo_bot(from_game, to_game));
+            let uno = Uno::new(2, 0);
+
+            let action = strategy.choose_action(&uno.view(0));
+
+            assert_eq!(action, uno.legal_actions(0)[0]);
+        }
+
+        #[test]
+        fn bots_are_told_when_an_action_isnt_allowed() {
+            let (mut strategy, mut from_game, mut to_game) = connect(DEFAULT_TIMEOUT);
+            let bot = thread::spawn(move || {
+                let mut observation = String::new();
+                from_game.read_line(&mut observation).unwrap();
+                // There's no calling Uno with a full hand.
+                writeln!(to_game, r#""call_uno""#).unwrap();
+                let mut error = String::new();
+                from_game.read_line(&mut error).unwrap();
+                let observation: serde_json::Value = serde_json::from_str(&observation).unwrap();
+                writeln!(to_game, "{}", observation["legal_actions"][0]).unwrap();
+                error
+            });
+            let uno = Uno::new(2, 0);
+
+            let action = strategy.choose_action(&uno.view(0));
+
+            assert_eq!(action, uno.legal_actions(0)[0]);
+            assert!(bot.join().unwrap().contains(r#""type":"error""#));
+        }
+
+        #[test]
+        fn bots_that_dont_answer_in_time_get_a_basic_move() {
+            let (mut strategy, _from_game, _to_game) = connect(Duration::from_millis(10));
+            let uno = Uno::new(2, 0);
+
+            let action = strategy.choose_action(&uno.view(0));
+
+            assert_eq!(action, BasicStrategy.choose_action(&uno.view(0)));
+        }
+
+        #[test]
+        fn observations_have_the_view_and_the_legal_actions() {
+            let uno = Uno::new(2, 0);
+            let view = uno.view(0);
+            let legal_actions = view.legal_actions();
+
+            let observation: serde_json::Value = serde_json::to_value(Message::Observation { view: &view, legal_actions: &legal_actions }).unwrap();
+
+            assert_eq!(observation["type"], "observation");
+            assert_eq!(observation["seat"], 0);
+            assert_eq!(observation["hand"].as_array().unwrap().len(), 7);
+            assert_eq!(observation["legal_actions"].as_array().unwrap().len(), legal_actions.len());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Medium computer players.
+
+use crate::{
+    ai::{explain, explained_call_uno_or_challenge, explained_draw_or_pass, play, Explanation, Strategy},
+    card::{Card, Effect},
+    game::Action,
+    view::PlayerView,
+};
+
+/// Opponents holding this many cards or fewer are about to go out.
+const CLOSE_TO_UNO: usize = 2;
+
+/// Scores each card it could play and plays the best one. It saves wilds for when nothing else can
+/// be played and wild draw cards for when the next player is about to go out, skips and draws on
+/// whoever's closest to winning, reverses play away from them, and gets rid of the cards worth the
+/// most points first.
+#[derive(Default)]
+pub struct HeuristicStrategy;
+
+impl Strategy for HeuristicStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let scores: Vec<(usize, i32)> = view.playable_card_indexes().into_iter()
+            .map(|card_index| (card_index, score_card(view, &view.hand[card_index])))
+            .collect();
+        let Some(&(card_index, _)) = scores.iter().max_by_key(|(_, score)| *score) else {
+            return explained_draw_or_pass(view);
+        };
+        let action = play(view, card_index);
+        let reason = "It scored each card it could play by its points and how it'd leave things, and played the best one.";
+        (action, Some(explain(view, &action, reason, scored_plays(&scores))))
+    }
+}
+
+/// Scores for playing the cards at some indexes, as actions.
+pub(super) fn scored_plays(scores: &[(usize, i32)]) -> Vec<(Action, f64)> {
+    scores.iter()
+        .map(|(card_index, score)| (Action::Play { card_index: *card_index, color: None, swap_with: None }, *score as f64))
+        .collect()
+}
+
+/// How good it'd be to play `card` right now. Higher is better.
+pub(super) fn score_card(view: &PlayerView, card: &Card) -> i32 {
+    let next_player = view.next_player_after(card);
+    let next_is_teammate = view.is_teammate(next_player);
+    let next_is_close_to_uno = !next_is_teammate && view.hand_sizes[next_player] <= CLOSE_TO_UNO;
+    let effects = view.rules.card_effects(card);
+    let hurts_next_player = effects.iter().any(|effect| {
+        matches!(effect, Effect::Skip | Effect::SkipEveryone | Effect::Draw(_) | Effect::DrawColor)
+    });
+
+    // Getting rid of points matters most, since they're what the winner scores.
+    let mut score = card.points();
+    // Sticking to a color keeps more of the hand playable.
+    score += 2 * view.hand.iter().filter(|hand_card| card.color.is_some() && hand_card.color == card.color).count() as i32;
+    if card.wild {
+        score -= 100;
+    }
+    if hurts_next_player {
+        if next_is_teammate {
+            score -= 200;
+        } else if next_is_close_to_uno {
+            score += 150;
+        } else if card.wild {
+            // Wild draw cards are worth the most saved for someone about to go out.
+            score -= 50;
+        }
+    }
+    if effects.contains(&Effect::Reverse) && view.next_player != view.previous_player {
+        // Send play away from whoever has fewer cards.
+        let next_cards = view.hand_sizes[view.next_player];
+        let previous_cards = view.hand_sizes[view.previous_player];
+        if !view.is_teammate(view.next_player) && next_cards < previous_cards {
+            score += 60;
+        } else if !view.is_teammate(view.previous_player) && previous_cards < next_cards {
+            score -= 60;
+        }
+    }
+    score
+}
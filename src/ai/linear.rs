@@ -0,0 +1,257 @@
+//! Tuned computer players.
+
+use std::{fmt::Display, fs, io::ErrorKind, str::FromStr};
+
+use crate::{
+    ai::{explain, explained_call_uno_or_challenge, play_with_wild_color, Explanation, Strategy},
+    card::{Card, Color, Effect},
+    game::Action,
+    view::PlayerView,
+};
+
+/// What moves are judged on, as they're named in weights files. Each is worked out from how
+/// things would stand right after the move.
+pub const FEATURES: [&str; 9] = [
+    // Cards left in hand.
+    "cards",
+    // Different colors left in hand.
+    "colors",
+    // Skips, reverses, and draw cards left in hand, not counting wilds.
+    "action-cards",
+    // Wilds left in hand.
+    "wilds",
+    // Points the card played is worth.
+    "points",
+    // Cards left in hand of the color on top.
+    "on-color",
+    // Cards the next player will have if they're an opponent, counting any they're made to draw.
+    "next-opponent-cards",
+    // Whether the move skips a teammate or makes them draw.
+    "hits-teammate",
+    // Whether the move is drawing a card.
+    "draws",
+];
+/// Weights picked by hand, which play about as well as a medium computer player. They're where
+/// tuning starts from when there's no weights file yet.
+const DEFAULT_WEIGHTS: [f64; FEATURES.len()] = [-10.0, -1.0, 1.0, 3.0, 0.2, 2.0, 1.0, -20.0, -1.0];
+
+/// How much each feature counts for. Saved to files as `feature = weight` lines.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Weights(pub [f64; FEATURES.len()]);
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights(DEFAULT_WEIGHTS)
+    }
+}
+
+impl Weights {
+    /// Loads weights from `path`. Nothing's been tuned before the file's been made, so a missing
+    /// file gives the default weights.
+    pub fn load(path: &str) -> Result<Weights, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse(),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Weights::default()),
+            Err(error) => Err(format!("Couldn't read the weights file {}: {}", path, error)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|error| format!("Couldn't save the weights file {}: {}", path, error))
+    }
+
+    /// How good a move looks. Higher is better.
+    pub fn evaluate(&self, view: &PlayerView, action: &Action) -> f64 {
+        features(view, action).iter().zip(self.0).map(|(feature, weight)| feature * weight).sum()
+    }
+}
+
+impl FromStr for Weights {
+    type Err = String;
+
+    /// Features a file leaves out keep their default weight.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let bad_line = || format!("\"{}\" should look like \"feature = weight\".", line);
+            let (name, weight) = line.split_once('=').ok_or_else(bad_line)?;
+            let feature = FEATURES.iter().position(|feature| *feature == name.trim())
+                .ok_or_else(|| format!("\"{}\" isn't a feature.", name.trim()))?;
+            weights.0[feature] = weight.trim().parse().map_err(|_| bad_line())?;
+        }
+        Ok(weights)
+    }
+}
+
+impl Display for Weights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, weight) in FEATURES.iter().zip(self.0) {
+            writeln!(f, "{} = {}", name, weight)?;
+        }
+        Ok(())
+    }
+}
+
+/// Weighs up every card it could play, every wild color, and drawing, and makes whichever move
+/// comes out best.
+#[derive(Default)]
+pub struct LinearStrategy {
+    pub weights: Weights,
+}
+
+impl Strategy for LinearStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let mut moves: Vec<Action> = Vec::new();
+        for card_index in view.playable_card_indexes() {
+            if view.hand[card_index].wild {
+                moves.extend(Color::for_side(view.side).map(|color| Action::Play { card_index, color: Some(color), swap_with: None }));
+            } else {
+                moves.push(Action::Play { card_index, color: None, swap_with: None });
+            }
+        }
+        let legal_actions = view.legal_actions();
+        moves.extend([Action::Draw, Action::Pass].into_iter().filter(|action| legal_actions.contains(action)));
+
+        let scores: Vec<(Action, f64)> = moves.into_iter()
+            .map(|action| (action, self.weights.evaluate(view, &action)))
+            .collect();
+        let (best_move, _) = *scores.iter()
+            .max_by(|(_, score), (_, other_score)| score.total_cmp(other_score))
+            .expect("there's always something to do on a turn");
+        let action = match best_move {
+            // Playing it properly picks who to swap with, and goes along with a teammate's signal.
+            Action::Play { card_index, color, .. } => play_with_wild_color(view, card_index, |_| color.unwrap()),
+            action => action,
+        };
+        let reason = "It added up how each move would leave its hand and the next player, using its tuned weights, and made the best one.";
+        (action, Some(explain(view, &action, reason, scores)))
+    }
+}
+
+/// Works out each of `FEATURES` for the player making `action`.
+fn features(view: &PlayerView, action: &Action) -> [f64; FEATURES.len()] {
+    let (rest_of_hand, played, top_color): (Vec<Card>, Option<Card>, Option<Color>) = match *action {
+        Action::Play { card_index, color, .. } => {
+            let card = view.hand[card_index];
+            let rest_of_hand = view.hand.iter().enumerate()
+                .filter(|(index, _)| *index != card_index)
+                .map(|(_, card)| *card)
+                .collect();
+            (rest_of_hand, Some(card), color.or(card.color))
+        }
+        _ => (view.hand.clone(), None, view.top_card.and_then(|card| card.color)),
+    };
+    let draws = *action == Action::Draw;
+
+    let mut colors: Vec<Color> = Vec::new();
+    for color in rest_of_hand.iter().filter_map(|card| card.color) {
+        if !colors.contains(&color) {
+            colors.push(color);
+        }
+    }
+    let action_cards = rest_of_hand.iter().filter(|card| !card.wild && card.number.is_none()).count();
+    let wilds = rest_of_hand.iter().filter(|card| card.wild).count();
+    let on_color = rest_of_hand.iter().filter(|card| top_color.is_some() && card.color == top_color).count();
+
+    let effects = played.map(|card| view.rules.card_effects(&card)).unwrap_or_default();
+    let next_player = played.map_or(view.next_player, |card| view.next_player_after(&card));
+    let forced_draw: i32 = effects.iter()
+        .map(|effect| match effect {
+            Effect::Draw(count) => *count,
+            // There's no telling how many, but it's usually a few.
+            Effect::DrawColor => 3,
+            _ => 0,
+        })
+        .sum();
+    let hurts_next_player = forced_draw > 0 || effects.iter().any(|effect| matches!(effect, Effect::Skip | Effect::SkipEveryone));
+    let next_opponent_cards = if view.is_teammate(next_player) {
+        0
+    } else {
+        let pending_draw = if forced_draw > 0 { view.pending_draw } else { 0 };
+        view.hand_sizes[next_player] + (forced_draw + pending_draw) as usize
+    };
+
+    [
+        (rest_of_hand.len() + draws as usize) as f64,
+        colors.len() as f64,
+        action_cards as f64,
+        wilds as f64,
+        played.map_or(0, |card| card.points()) as f64,
+        on_color as f64,
+        next_opponent_cards as f64,
+        (hurts_next_player && view.is_teammate(next_player)) as u8 as f64,
+        draws as u8 as f64,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_str {
+        use super::super::*;
+
+        #[test]
+        fn reads_back_what_it_writes() {
+            let mut weights = Weights::default();
+            weights.0[2] = -0.25;
+
+            let read_back: Weights = weights.to_string().parse().unwrap();
+
+            assert_eq!(read_back, weights);
+        }
+
+        #[test]
+        fn features_left_out_keep_their_default_weight() {
+            let weights: Weights = "wilds = 7".parse().unwrap();
+
+            assert_eq!(weights.0[3], 7.0);
+            assert_eq!(weights.0[0], DEFAULT_WEIGHTS[0]);
+        }
+
+        #[test]
+        fn rejects_unknown_features() {
+            assert!("luck = 3".parse::<Weights>().is_err());
+            assert!("wilds".parse::<Weights>().is_err());
+        }
+    }
+
+    mod linear_strategy {
+        use super::super::*;
+        use crate::game::Uno;
+
+        #[test]
+        fn only_makes_legal_moves() {
+            let mut uno = Uno::new(0, 3);
+            for seat in uno.ai_seats() {
+                uno.set_strategy(seat, Box::new(LinearStrategy::default()));
+            }
+
+            for _ in 0..200 {
+                if uno.game_over() {
+                    break;
+                }
+                let seat = uno.current_player();
+                let action = LinearStrategy::default().choose_action(&uno.view(seat));
+                assert!(uno.legal_actions(seat).contains(&action), "{:?} isn't legal", action);
+                uno.automate_current_player_turn();
+            }
+        }
+
+        #[test]
+        fn follows_the_weights() {
+            let uno = Uno::new(1, 1);
+            let view = uno.view(0);
+            let mut hoards_cards = LinearStrategy { weights: Weights([0.0; FEATURES.len()]) };
+            hoards_cards.weights.0[0] = 100.0;
+
+            assert_eq!(hoards_cards.choose_action(&view), Action::Draw);
+        }
+    }
+}
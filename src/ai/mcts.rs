@@ -0,0 +1,325 @@
+//! Expert computer players.
+
+use std::time::{Duration, Instant};
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::{
+    ai::{
+        counting::{take_card, CardCount}, explain, explained_call_uno_or_challenge, BasicStrategy, Explanation,
+        HeuristicStrategy, Strategy,
+    },
+    card::{Card, Side},
+    game::{Action, Uno},
+    view::PlayerView,
+};
+
+/// Play-outs that go on longer than this count as half a win for everyone.
+const MAX_PLAYOUT_ACTIONS: usize = 500;
+/// How much the search favors moves it hasn't tried much over moves that have done well.
+const EXPLORATION: f64 = 0.7;
+
+/// Searches with information set Monte Carlo tree search. Each iteration deals out the cards it
+/// can't see in a way that fits what it knows, picks moves down a tree shared by every deal, plays
+/// the rest of the game out like a medium player, and counts who won. The move that was picked the
+/// most gets played.
+pub struct MctsStrategy {
+    /// The most deals to play out per move.
+    pub iterations: usize,
+    /// The longest to search per move, however many iterations are left.
+    pub time_limit: Duration,
+}
+
+impl Default for MctsStrategy {
+    fn default() -> Self {
+        MctsStrategy {
+            iterations: 1000,
+            time_limit: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Strategy for MctsStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        if let Some(explained_action) = explained_call_uno_or_challenge(view) {
+            return explained_action;
+        }
+        let mut rng = thread_rng();
+        let dealer = Dealer::new(view);
+        // The real position decides which moves there are to pick between.
+        let moves = legal_moves(&dealer.deal(view, &mut rng));
+        if moves.len() <= 1 {
+            let action = moves.first().map_or(Action::Pass, |(action, _)| *action);
+            return (action, Some(explain(view, &action, "It was the only move there was to make.", Vec::new())));
+        }
+
+        let mut tree = vec![Node::root()];
+        let start = Instant::now();
+        let mut deals = 0;
+        for _ in 0..self.iterations {
+            if start.elapsed() > self.time_limit {
+                break;
+            }
+            let state = dealer.deal(view, &mut rng);
+            search(&mut tree, state, view, &mut rng);
+            deals += 1;
+        }
+
+        let best_move = tree[0].children.iter()
+            .map(|child| &tree[*child])
+            .max_by(|a, b| a.visits.total_cmp(&b.visits))
+            .and_then(|node| node.last_move);
+        let Some(&(action, _)) = moves.iter().find(|(_, legal_move)| best_move.is_some_and(|best_move| best_move.is(legal_move))) else {
+            return HeuristicStrategy.choose_explained_action(view);
+        };
+        // How often each move it tried won, out of 100.
+        let scores = moves.iter()
+            .filter_map(|(action, legal_move)| {
+                let child = tree[0].children.iter().map(|child| &tree[*child]).find(|node| node.last_move.is_some_and(|last_move| last_move.is(legal_move)))?;
+                Some((*action, 100.0 * child.wins / child.visits))
+            })
+            .collect();
+        let reason = format!(
+            "It played out {} guesses at the cards it can't see and made the move it tried most. Scores are how often each move won, out of 100.",
+            deals,
+        );
+        (action, Some(explain(view, &action, &reason, scores)))
+    }
+}
+
+/// A move that means the same thing in every deal, since card indexes don't.
+#[derive(Copy, Clone)]
+pub(super) enum Move {
+    /// The card has the color picked for it if it's a wild.
+    Play { card: Card, swap_with: Option<usize> },
+    Draw,
+    Pass,
+}
+
+impl Move {
+    fn is(&self, other: &Move) -> bool {
+        match (self, other) {
+            (Move::Play { card, swap_with }, Move::Play { card: other_card, swap_with: other_swap_with }) => {
+                card.is_same_card_as(other_card) && card.color == other_card.color && swap_with == other_swap_with
+            }
+            (Move::Draw, Move::Draw) | (Move::Pass, Move::Pass) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Every move worth searching through for the current player. Signaling doesn't change how the
+/// game goes, calling Uno and challenging are always worth doing so they're done before searching,
+/// and drawing doesn't end the turn, so it's only worth it when there's nothing to play.
+pub(super) fn legal_moves(state: &Uno) -> Vec<(Action, Move)> {
+    let hand = state.hand(state.current_player());
+    let legal_actions = state.legal_actions(state.current_player());
+    let can_play = legal_actions.iter().any(|action| matches!(action, Action::Play { .. }));
+    legal_actions.into_iter()
+        .filter_map(|action| match action {
+            Action::Play { card_index, color, swap_with } => {
+                let mut card = hand[card_index];
+                if card.wild {
+                    card.color = color;
+                }
+                Some((action, Move::Play { card, swap_with }))
+            }
+            Action::Draw if can_play => None,
+            Action::Draw => Some((action, Move::Draw)),
+            Action::Pass => Some((action, Move::Pass)),
+            Action::CallUno | Action::Challenge(_) | Action::Signal(_) => None,
+        })
+        .collect()
+}
+
+struct Node {
+    /// The move that led here. Only the root doesn't have one.
+    last_move: Option<Move>,
+    /// Who made `last_move`.
+    player: usize,
+    visits: f64,
+    /// How many of the visits `player` won, with ties and unfinished games counting as halves.
+    wins: f64,
+    /// How many times this node's move was one of the moves that could be picked.
+    availability: f64,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn root() -> Node {
+        Node { last_move: None, player: 0, visits: 0.0, wins: 0.0, availability: 0.0, children: Vec::new() }
+    }
+
+    fn upper_confidence_bound(&self) -> f64 {
+        self.wins / self.visits + EXPLORATION * (self.availability.ln() / self.visits).sqrt()
+    }
+}
+
+/// Runs one iteration of the search on one deal of the cards.
+fn search(tree: &mut Vec<Node>, mut state: Uno, view: &PlayerView, rng: &mut impl Rng) {
+    let mut path = vec![0];
+    let mut node_index = 0;
+    while !state.game_over() {
+        let moves = legal_moves(&state);
+        if moves.is_empty() {
+            break;
+        }
+        let mut untried_moves = Vec::new();
+        let mut tried_children = Vec::new();
+        for (action, legal_move) in &moves {
+            match tree[node_index].children.iter().find(|child| tree[**child].last_move.is_some_and(|child_move| child_move.is(legal_move))) {
+                Some(child) => tried_children.push((*child, *action)),
+                None => untried_moves.push((*action, *legal_move)),
+            }
+        }
+        for (child, _) in &tried_children {
+            tree[*child].availability += 1.0;
+        }
+
+        if let Some((action, legal_move)) = untried_moves.choose(rng).copied() {
+            tree.push(Node {
+                last_move: Some(legal_move),
+                player: state.current_player(),
+                visits: 0.0,
+                wins: 0.0,
+                availability: 1.0,
+                children: Vec::new(),
+            });
+            let child = tree.len() - 1;
+            tree[node_index].children.push(child);
+            path.push(child);
+            state.apply_action(state.current_player(), action).expect("legal actions can be applied");
+            break;
+        }
+
+        let (child, action) = *tried_children.iter()
+            .max_by(|(a, _), (b, _)| tree[*a].upper_confidence_bound().total_cmp(&tree[*b].upper_confidence_bound()))
+            .unwrap();
+        state.apply_action(state.current_player(), action).expect("legal actions can be applied");
+        node_index = child;
+        path.push(child);
+    }
+
+    play_out(&mut state);
+    for node_index in path {
+        let node = &mut tree[node_index];
+        node.visits += 1.0;
+        node.wins += reward(&state, view, node.player);
+    }
+}
+
+/// Plays the game to the end with every player playing like a medium player.
+fn play_out(state: &mut Uno) {
+    let mut strategy = HeuristicStrategy;
+    for _ in 0..MAX_PLAYOUT_ACTIONS {
+        if state.game_over() {
+            return;
+        }
+        let view = state.view(state.current_player());
+        let action = strategy.choose_action(&view);
+        if state.apply_action(state.current_player(), action).is_err() {
+            let fallback_action = BasicStrategy.choose_action(&state.view(state.current_player()));
+            // Only happens when there's nothing left to draw, so the game can't go on.
+            if state.apply_action(state.current_player(), fallback_action).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// How well the game went for `player`, from 0 for losing to 1 for winning.
+fn reward(state: &Uno, view: &PlayerView, player: usize) -> f64 {
+    let Some(winning_player) = state.winning_player_index().map(|winner| winner as usize) else {
+        return 0.5;
+    };
+    if view.rules.teams.is_some() {
+        return if view.teams[winning_player] == view.teams[player] { 1.0 } else { 0.0 };
+    }
+    if view.rules.last_player_standing {
+        let finishing_order = state.finishing_order();
+        let place = finishing_order.iter().position(|seat| *seat as usize == player).unwrap();
+        return 1.0 - place as f64 / (finishing_order.len() - 1) as f64;
+    }
+    if winning_player == player { 1.0 } else { 0.0 }
+}
+
+/// Deals out the cards a player can't see, keeping to what they know about other players' hands.
+pub(super) struct Dealer {
+    count: CardCount,
+    /// Cards that were seen played, which are in the discard pile unless it's been shuffled back
+    /// into the deck. The top card is still in here, since its back can't be seen.
+    played: Vec<Card>,
+}
+
+impl Dealer {
+    pub(super) fn new(view: &PlayerView) -> Dealer {
+        let count = CardCount::new(view, None);
+        let mut played: Vec<Card> = view.rules.mode.deck();
+        if view.side == Side::Dark {
+            played.iter_mut().for_each(Card::flip);
+        }
+        for card in count.unseen.iter().chain(&count.hand) {
+            if let Some(index) = played.iter().position(|played_card| played_card.is_same_card_as(card)) {
+                played.swap_remove(index);
+            }
+        }
+        Dealer { count, played }
+    }
+
+    pub(super) fn deal(&self, view: &PlayerView, rng: &mut impl Rng) -> Uno {
+        // The player's own cards go back in too, so their hidden backs get guessed again.
+        let mut cards: Vec<Card> = self.count.unseen.iter().chain(&self.count.hand).copied().collect();
+        cards.shuffle(rng);
+        let mut hands: Vec<Vec<Card>> = vec![Vec::new(); view.hand_sizes.len()];
+        hands[view.seat] = view.hand.iter().map(|card| take_card(&mut cards, card).unwrap_or(*card)).collect();
+
+        let cards_needed = view.deck_size + (0..view.hand_sizes.len())
+            .filter(|seat| *seat != view.seat)
+            .map(|seat| view.hand_sizes[seat])
+            .sum::<usize>();
+        let mut played = self.played.clone();
+        played.shuffle(rng);
+        // The top card's back is face down on the pile, so it gets guessed too.
+        let top_card = view.top_card.map(|top_card| Card {
+            flip_side: take_card(&mut played, &top_card).and_then(|card| card.flip_side),
+            ..top_card
+        });
+        if cards.len() < cards_needed {
+            // The discard pile must've been shuffled back in.
+            cards.extend(played.drain(..(cards_needed - cards.len()).min(played.len())));
+        }
+
+        for (seat, hand) in hands.iter_mut().enumerate() {
+            if seat == view.seat {
+                continue;
+            }
+            // Everyone can see the backs of each other's cards in Uno Flip.
+            for back in &view.backs[seat] {
+                if let Some(card_index) = cards.iter().position(|card| card.flip_side == Some(*back)) {
+                    hand.push(cards.swap_remove(card_index));
+                }
+            }
+            let lacking = &self.count.lacking[seat];
+            // Give players the colors they're thought to be out of only once nothing else is left.
+            let mut card_index = 0;
+            while hand.len() < view.hand_sizes[seat] && card_index < cards.len() {
+                if cards[card_index].color.is_some_and(|color| lacking.contains(&color)) {
+                    card_index += 1;
+                } else {
+                    hand.push(cards.swap_remove(card_index));
+                }
+            }
+            while hand.len() < view.hand_sizes[seat] {
+                match cards.pop() {
+                    Some(card) => hand.push(card),
+                    None => break,
+                }
+            }
+        }
+        Uno::from_position(&PlayerView { top_card, ..view.clone() }, hands, cards, played)
+    }
+}
@@ -0,0 +1,38 @@
+//! Easy computer players.
+
+use rand::{seq::SliceRandom, thread_rng, Rng};
+
+use crate::{
+    ai::{explain, explained_draw_or_pass, Explanation, Strategy},
+    card::Color,
+    game::Action,
+    view::PlayerView,
+};
+
+/// Plays any card it can at random, with a random color for wilds. Draws when it can't play, and
+/// never catches anyone not calling Uno.
+#[derive(Default)]
+pub struct RandomStrategy;
+
+impl Strategy for RandomStrategy {
+    fn choose_action(&mut self, view: &PlayerView) -> Action {
+        self.choose_explained_action(view).0
+    }
+
+    fn choose_explained_action(&mut self, view: &PlayerView) -> (Action, Option<Explanation>) {
+        let mut rng = thread_rng();
+        // Forgets to call Uno about half the time.
+        if view.hand.len() == 2 && !view.called_uno && rng.gen_bool(0.5) {
+            let action = Action::CallUno;
+            return (action, Some(explain(view, &action, "It remembered to call Uno this time, which it only does about half the time.", Vec::new())));
+        }
+        match view.playable_card_indexes().choose(&mut rng) {
+            Some(&card_index) => {
+                let color = view.hand[card_index].wild.then(|| *Color::for_side(view.side).choose(&mut rng).unwrap());
+                let action = Action::Play { card_index, color, swap_with: None };
+                (action, Some(explain(view, &action, "It picked a card it could play at random.", Vec::new())))
+            }
+            None => explained_draw_or_pass(view),
+        }
+    }
+}
@@ -1,62 +1,292 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Color {
     Red,
     Blue,
     Green,
     Yellow,
+    // Dark side colors, only used by Uno Flip.
+    Pink,
+    Teal,
+    Orange,
+    Purple,
+}
+
+impl Color {
+    /// The colors a wild card can be given while the game is on the given side.
+    pub fn for_side(side: Side) -> [Color; 4] {
+        match side {
+            Side::Light => [Color::Red, Color::Blue, Color::Yellow, Color::Green],
+            Side::Dark => [Color::Pink, Color::Teal, Color::Orange, Color::Purple],
+        }
+    }
+
+    pub fn side(&self) -> Side {
+        match self {
+            Color::Red | Color::Blue | Color::Green | Color::Yellow => Side::Light,
+            Color::Pink | Color::Teal | Color::Orange | Color::Purple => Side::Dark,
+        }
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Color::Red => "red",
+            Color::Blue => "blue",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Pink => "pink",
+            Color::Teal => "teal",
+            Color::Orange => "orange",
+            Color::Purple => "purple",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Which face of a two faced Uno Flip card is showing. Regular Uno games are always on the light
+/// side.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    #[default]
+    Light,
+    Dark,
 }
 
-impl ToString for Color {
-    fn to_string(&self) -> String {
+impl Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Color::Red => "red".to_string(),
-            Color::Blue => "blue".to_string(),
-            Color::Green => "green".to_string(),
-            Color::Yellow => "yellow".to_string(),
+            Side::Light => write!(f, "light"),
+            Side::Dark => write!(f, "dark"),
         }
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TurnEffect {
     Skip,
     Reverse,
+    /// Flips every card in the game over to its other side.
+    Flip,
+    /// Skips every other player so the player who played it goes again.
+    SkipEveryone,
+    /// The player also discards every other card in their hand of the same color.
+    DiscardAll,
+    /// The player swaps hands with another player.
+    SwapHands,
+    /// Everyone's cards get shuffled together and dealt back out.
+    ShuffleHands,
+    /// A blank card whose effect is whatever the players decided on before the game.
+    Custom,
 }
 
-impl ToString for TurnEffect {
-    fn to_string(&self) -> String {
+impl Display for TurnEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TurnEffect::Skip => "skip".to_string(),
-            TurnEffect::Reverse => "reverse".to_string(),
+            TurnEffect::Skip => write!(f, "skip"),
+            TurnEffect::Reverse => write!(f, "reverse"),
+            TurnEffect::Flip => write!(f, "flip"),
+            TurnEffect::SkipEveryone => write!(f, "skip everyone"),
+            TurnEffect::DiscardAll => write!(f, "discard all"),
+            TurnEffect::SwapHands => write!(f, "swap hands"),
+            TurnEffect::ShuffleHands => write!(f, "shuffle hands"),
+            TurnEffect::Custom => write!(f, "customizable"),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DrawEffect {
     Draw(i32),
+    /// The next player keeps drawing until they draw a card of the wild card's chosen color.
+    DrawColor,
 }
 
-impl ToString for DrawEffect {
-    fn to_string(&self) -> String {
+impl Display for DrawEffect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            DrawEffect::Draw(num_cards) => format!("draw {}", num_cards),
+            DrawEffect::Draw(num_cards) => write!(f, "draw {}", num_cards),
+            DrawEffect::DrawColor => write!(f, "draw color"),
+        }
+    }
+}
+
+/// Something that happens when a card is played. Cards list their effects with `Card::effects`,
+/// and the game resolves them one after another.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    Reverse,
+    Skip,
+    SkipEveryone,
+    Draw(i32),
+    DrawColor,
+    DiscardAll,
+    SwapHands,
+    ShuffleHands,
+    Flip,
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Effect::Reverse => write!(f, "reverse"),
+            Effect::Skip => write!(f, "skip"),
+            Effect::SkipEveryone => write!(f, "skip everyone"),
+            Effect::Draw(num_cards) => write!(f, "draw {}", num_cards),
+            Effect::DrawColor => write!(f, "draw color"),
+            Effect::DiscardAll => write!(f, "discard all"),
+            Effect::SwapHands => write!(f, "swap hands"),
+            Effect::ShuffleHands => write!(f, "shuffle hands"),
+            Effect::Flip => write!(f, "flip"),
+        }
+    }
+}
+
+/// Parses the names used on the command line, like "swap-hands" or "draw-3".
+impl FromStr for Effect {
+    type Err = String;
+
+    fn from_str(effect_name: &str) -> Result<Self, Self::Err> {
+        match effect_name {
+            "reverse" => Ok(Effect::Reverse),
+            "skip" => Ok(Effect::Skip),
+            "skip-everyone" => Ok(Effect::SkipEveryone),
+            "draw-color" => Ok(Effect::DrawColor),
+            "discard-all" => Ok(Effect::DiscardAll),
+            "swap-hands" => Ok(Effect::SwapHands),
+            "shuffle-hands" => Ok(Effect::ShuffleHands),
+            "flip" => Ok(Effect::Flip),
+            draw if draw.starts_with("draw-") => draw["draw-".len()..]
+                .parse::<i32>()
+                .map(Effect::Draw)
+                .map_err(|_| format!("\"{}\" isn't a number of cards to draw.", &draw["draw-".len()..])),
+            _ => Err(format!("\"{}\" isn't a card effect.", effect_name)),
         }
     }
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+/// One side of a card. Regular cards only have the side described by the fields on `Card`, while
+/// Uno Flip cards also keep the face that is currently turned down.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct CardFace {
+    pub number: Option<i32>,
+    pub color: Option<Color>,
+    pub turn_effect: Option<TurnEffect>,
+    pub draw_effect: Option<DrawEffect>,
+    pub wild: bool,
+}
+
+/// The fields on a card describe the face that is showing. Uno Flip cards also have a
+/// `flip_side`, which gets swapped with the showing face whenever a flip card is played.
+#[derive(Default, Debug, Copy, Clone, Serialize)]
 pub struct Card {
     pub number: Option<i32>,
     pub color: Option<Color>,
     pub turn_effect: Option<TurnEffect>,
     pub draw_effect: Option<DrawEffect>,
     pub wild: bool,
+    pub flip_side: Option<CardFace>,
 }
 
 impl Card {
+    /// Creates a two faced Uno Flip card with `light` showing.
+    pub fn two_faced(light: Card, dark: Card) -> Card {
+        Card {
+            flip_side: Some(dark.face()),
+            ..light
+        }
+    }
+
+    /// The effects of the face that is showing, in the order they happen. Turn effects come before
+    /// draw effects so a reverse draw card makes the previous player draw. Custom cards have no
+    /// effects of their own, the game decides what they do.
+    pub fn effects(&self) -> Vec<Effect> {
+        let mut effects: Vec<Effect> = Vec::new();
+        match self.turn_effect {
+            Some(TurnEffect::Skip) => effects.push(Effect::Skip),
+            Some(TurnEffect::Reverse) => effects.push(Effect::Reverse),
+            Some(TurnEffect::Flip) => effects.push(Effect::Flip),
+            Some(TurnEffect::SkipEveryone) => effects.push(Effect::SkipEveryone),
+            Some(TurnEffect::DiscardAll) => effects.push(Effect::DiscardAll),
+            Some(TurnEffect::SwapHands) => effects.push(Effect::SwapHands),
+            Some(TurnEffect::ShuffleHands) => effects.push(Effect::ShuffleHands),
+            Some(TurnEffect::Custom) | None => (),
+        }
+        match self.draw_effect {
+            Some(DrawEffect::Draw(num_cards)) => effects.push(Effect::Draw(num_cards)),
+            Some(DrawEffect::DrawColor) => effects.push(Effect::DrawColor),
+            None => (),
+        }
+        effects
+    }
+
+    /// What the card is worth to whoever wins while it's still in someone's hand.
+    pub fn points(&self) -> i32 {
+        if self.wild {
+            50
+        } else if self.turn_effect.is_some() || self.draw_effect.is_some() {
+            20
+        } else {
+            self.number.unwrap_or(0)
+        }
+    }
+
+    /// The face that is currently showing.
+    pub fn face(&self) -> CardFace {
+        CardFace {
+            number: self.number,
+            color: self.color,
+            turn_effect: self.turn_effect,
+            draw_effect: self.draw_effect,
+            wild: self.wild,
+        }
+    }
+
+    /// Turns the card over. Cards without a flip side stay the same.
+    pub fn flip(&mut self) {
+        if let Some(flip_side) = self.flip_side {
+            let mut face_going_down = self.face();
+            // A wild's picked color only matters while it's on top of the discard pile.
+            if face_going_down.wild {
+                face_going_down.color = None;
+            }
+            self.number = flip_side.number;
+            self.color = flip_side.color;
+            self.turn_effect = flip_side.turn_effect;
+            self.draw_effect = flip_side.draw_effect;
+            self.wild = flip_side.wild;
+            self.flip_side = Some(face_going_down);
+        }
+    }
+
+    /// Whether two cards are copies of the same card, whichever side is up and whatever color a
+    /// wild was given. A card with its back hidden matches any card with the same face showing.
+    pub fn is_same_card_as(&self, other: &Card) -> bool {
+        let faces = |card: &Card| {
+            let mut face = card.face();
+            if face.wild {
+                face.color = None;
+            }
+            (face, card.flip_side)
+        };
+        let (face, flip_side) = faces(self);
+        let (other_face, other_flip_side) = faces(other);
+        if flip_side.is_none() != other_flip_side.is_none() {
+            return face == other_face;
+        }
+        (face == other_face && flip_side == other_flip_side)
+            || (Some(face) == other_flip_side && flip_side == Some(other_face))
+    }
+
     fn render(&self) -> String {
         let mut description_parts: Vec<String> = Vec::new();
         if self.wild {
@@ -78,6 +308,19 @@ impl Card {
     }
 }
 
+impl From<CardFace> for Card {
+    fn from(face: CardFace) -> Self {
+        Card {
+            number: face.number,
+            color: face.color,
+            turn_effect: face.turn_effect,
+            draw_effect: face.draw_effect,
+            wild: face.wild,
+            flip_side: None,
+        }
+    }
+}
+
 impl Display for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.render())
@@ -87,10 +330,14 @@ impl Display for Card {
 /// Used to help make lots of cards with not a lot of typing.
 /// Can take something like "red 5" and give back a card instance that's a red 5.
 /// Or you can do "green reverse" to get a green reverse card.
-/// Only catch is for draw effects only "draw2" and "draw4" are supported.
+/// Only catch is draw effects are written without a space, like "draw2" or "draw5".
 /// So to get a red draw2 you'd have to do Card::from("red draw2");
+/// Two faced Uno Flip cards separate their sides with a slash, like "red 5 / pink skip".
 impl From<&str> for Card {
     fn from(card_string: &str) -> Self {
+        if let Some((light, dark)) = card_string.split_once('/') {
+            return Card::two_faced(Card::from(light.trim()), Card::from(dark.trim()));
+        }
         let mut card = Card::default();
         for card_descriptor in card_string.split(' ') {
             match card_descriptor {
@@ -98,6 +345,10 @@ impl From<&str> for Card {
                 "blue" => card.color = Some(Color::Blue),
                 "yellow" => card.color = Some(Color::Yellow),
                 "green" => card.color = Some(Color::Green),
+                "pink" => card.color = Some(Color::Pink),
+                "teal" => card.color = Some(Color::Teal),
+                "orange" => card.color = Some(Color::Orange),
+                "purple" => card.color = Some(Color::Purple),
                 "0" => card.number = Some(0),
                 "1" => card.number = Some(1),
                 "2" => card.number = Some(2),
@@ -110,9 +361,19 @@ impl From<&str> for Card {
                 "9" => card.number = Some(9),
                 "skip" => card.turn_effect = Some(TurnEffect::Skip),
                 "reverse" => card.turn_effect = Some(TurnEffect::Reverse),
-                "draw2" => card.draw_effect = Some(DrawEffect::Draw(2)),
-                "draw4" => card.draw_effect = Some(DrawEffect::Draw(4)),
+                "flip" => card.turn_effect = Some(TurnEffect::Flip),
+                "skipeveryone" => card.turn_effect = Some(TurnEffect::SkipEveryone),
+                "discardall" => card.turn_effect = Some(TurnEffect::DiscardAll),
+                "swaphands" => card.turn_effect = Some(TurnEffect::SwapHands),
+                "shufflehands" => card.turn_effect = Some(TurnEffect::ShuffleHands),
+                "custom" => card.turn_effect = Some(TurnEffect::Custom),
+                "drawcolor" => card.draw_effect = Some(DrawEffect::DrawColor),
                 "wild" => card.wild = true,
+                draw if draw.starts_with("draw") => {
+                    if let Ok(num_cards) = draw["draw".len()..].parse::<i32>() {
+                        card.draw_effect = Some(DrawEffect::Draw(num_cards));
+                    }
+                }
                 _ => (),
             };
         }
@@ -168,12 +429,14 @@ impl PartialEq for Card {
         if self.wild != other.wild {
             return false;
         }
-        return true;
+        // check flip side
+        self.flip_side == other.flip_side
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #[allow(clippy::field_reassign_with_default)]
     mod card_equality {
         use crate::card::{self, Card, Color, DrawEffect};
 
@@ -233,8 +496,9 @@ mod tests {
         }
     }
 
+    #[allow(clippy::field_reassign_with_default)]
     mod from_str {
-        use crate::card::{Card, Color, DrawEffect};
+        use crate::card::{Card, Color, DrawEffect, TurnEffect};
 
         #[test]
         fn create_blue_1() {
@@ -262,5 +526,133 @@ mod tests {
             let received_card = Card::from("wild yellow");
             assert!(wild_yellow == received_card);
         }
+
+        #[test]
+        fn create_pink_draw_5() {
+            let pink_draw_5 = Card { color: Some(Color::Pink), draw_effect: Some(DrawEffect::Draw(5)), ..Card::default() };
+            let received_card = Card::from("pink draw5");
+            assert!(pink_draw_5 == received_card);
+        }
+
+        #[test]
+        fn create_two_faced_card() {
+            let received_card = Card::from("red 5 / teal skipeveryone");
+            assert_eq!(received_card.color, Some(Color::Red));
+            assert_eq!(received_card.number, Some(5));
+            let flip_side = received_card.flip_side.unwrap();
+            assert_eq!(flip_side.color, Some(Color::Teal));
+            assert_eq!(flip_side.turn_effect, Some(TurnEffect::SkipEveryone));
+        }
+    }
+
+    mod effects {
+        use crate::card::{Card, Effect};
+
+        #[test]
+        fn number_cards_have_no_effects() {
+            assert!(Card::from("red 5").effects().is_empty());
+        }
+
+        #[test]
+        fn wild_reverse_draw_4_reverses_before_drawing() {
+            let effects = Card::from("wild reverse draw4").effects();
+            assert_eq!(effects, vec![Effect::Reverse, Effect::Draw(4)]);
+        }
+
+        #[test]
+        fn custom_cards_leave_their_effect_up_to_the_game() {
+            assert!(Card::from("wild custom").effects().is_empty());
+        }
+
+        #[test]
+        fn parses_effect_names() {
+            assert_eq!("swap-hands".parse::<Effect>(), Ok(Effect::SwapHands));
+            assert_eq!("draw-3".parse::<Effect>(), Ok(Effect::Draw(3)));
+            assert!("draw-lots".parse::<Effect>().is_err());
+            assert!("explode".parse::<Effect>().is_err());
+        }
+    }
+
+    mod points {
+        use crate::card::Card;
+
+        #[test]
+        fn number_cards_are_worth_their_number() {
+            assert_eq!(Card::from("red 7").points(), 7);
+        }
+
+        #[test]
+        fn action_cards_are_worth_20() {
+            assert_eq!(Card::from("blue skip").points(), 20);
+            assert_eq!(Card::from("green draw2").points(), 20);
+        }
+
+        #[test]
+        fn wilds_are_worth_50() {
+            assert_eq!(Card::from("wild draw4").points(), 50);
+        }
+    }
+
+    mod flip {
+        use crate::card::{Card, Color};
+
+        #[test]
+        fn flipping_shows_the_other_side() {
+            let mut card = Card::from("red 5 / teal skipeveryone");
+            card.flip();
+            assert!(card == Card::from("teal skipeveryone / red 5"));
+        }
+
+        #[test]
+        fn flipping_twice_shows_the_original_side() {
+            let mut card = Card::from("blue flip / orange 3");
+            card.flip();
+            card.flip();
+            assert!(card == Card::from("blue flip / orange 3"));
+        }
+
+        #[test]
+        fn flipping_a_wild_down_forgets_its_picked_color() {
+            let mut card = Card::from("wild / purple reverse");
+            card.color = Some(Color::Red);
+            card.flip();
+            card.flip();
+            assert!(card.wild);
+            assert!(card.color.is_none());
+        }
+
+        #[test]
+        fn cards_without_a_flip_side_dont_change() {
+            let mut card = Card::from("green 7");
+            card.flip();
+            assert!(card == Card::from("green 7"));
+        }
+    }
+
+    mod is_same_card_as {
+        use crate::card::{Card, Color};
+
+        #[test]
+        fn wilds_are_the_same_whatever_color_they_were_given() {
+            let mut played_wild = Card::from("wild draw4");
+            played_wild.color = Some(Color::Green);
+            assert!(played_wild.is_same_card_as(&Card::from("wild draw4")));
+            assert!(!played_wild.is_same_card_as(&Card::from("wild")));
+        }
+
+        #[test]
+        fn two_faced_cards_are_the_same_whichever_side_is_up() {
+            let mut card = Card::from("red 5 / teal skipeveryone");
+            card.flip();
+            assert!(card.is_same_card_as(&Card::from("red 5 / teal skipeveryone")));
+            assert!(!card.is_same_card_as(&Card::from("red 5 / teal 5")));
+        }
+
+        #[test]
+        fn cards_with_a_hidden_back_match_on_the_side_thats_up() {
+            let card = Card::from("red 5");
+            assert!(card.is_same_card_as(&Card::from("red 5 / teal skipeveryone")));
+            assert!(!card.is_same_card_as(&Card::from("teal skipeveryone / red 5")));
+        }
     }
 }
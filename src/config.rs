@@ -0,0 +1,405 @@
+//! This mod is for game setups that can be saved and loaded again.
+
+use std::{fmt::Display, fs, str::FromStr, time::Duration};
+
+use crate::{
+    ai::{Difficulty, ExpectiminimaxStrategy, ExternalStrategy, LinearStrategy, MctsStrategy, Strategy, Weights, DEFAULT_BOT_TIMEOUT},
+    card::Effect,
+    game::{GameMode, Rules},
+    ui::Pace,
+};
+
+/// Everything needed to set up a game. Saved to files as `key = value` lines, with the same names
+/// as the command line options.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Config {
+    pub rules: Rules,
+    pub humans: i32,
+    pub ais: i32,
+    /// The names human players are rated under, in seating order. Humans without a name aren't
+    /// rated.
+    pub names: Vec<String>,
+    /// How well each computer player plays, in seating order. Computer players past the end of
+    /// the list play at the last listed difficulty.
+    pub difficulties: Vec<Difficulty>,
+    /// How many deals expert players search through per move.
+    pub expert_iterations: usize,
+    /// How long expert players can search for per move.
+    pub expert_time: Duration,
+    /// How many moves ahead duel players look.
+    pub duel_depth: usize,
+    /// How long duel players can search for per move.
+    pub duel_time: Duration,
+    /// The command that runs the program playing bot computer players.
+    pub bot: Option<String>,
+    /// How long bots get to answer each time it's their move.
+    pub bot_timeout: Duration,
+    /// The file tuned computer players get their weights from, and tuning saves them to.
+    pub weights: String,
+    /// How well the computer player giving humans hints plays.
+    pub hint_difficulty: Difficulty,
+    /// Ranked games are for finding out how good players really are, so there are no hints.
+    pub ranked: bool,
+    /// How computer players' moves are shown.
+    pub pace: Pace,
+}
+
+/// Where tuned weights are kept unless the `weights` setting says otherwise.
+pub const DEFAULT_WEIGHTS_PATH: &str = "weights.txt";
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            rules: Rules::default(),
+            humans: 1,
+            ais: 1,
+            names: Vec::new(),
+            difficulties: vec![Difficulty::default()],
+            expert_iterations: MctsStrategy::default().iterations,
+            expert_time: MctsStrategy::default().time_limit,
+            duel_depth: ExpectiminimaxStrategy::default().depth,
+            duel_time: ExpectiminimaxStrategy::default().time_limit,
+            bot: None,
+            bot_timeout: DEFAULT_BOT_TIMEOUT,
+            weights: DEFAULT_WEIGHTS_PATH.to_string(),
+            hint_difficulty: Difficulty::Hard,
+            ranked: false,
+            pace: Pace::Delay(Duration::from_millis(800)),
+        }
+    }
+}
+
+impl Config {
+    pub fn load(path: &str) -> Result<Config, String> {
+        fs::read_to_string(path)
+            .map_err(|error| format!("Couldn't read the config file {}: {}", path, error))?
+            .parse()
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|error| format!("Couldn't save the config file {}: {}", path, error))
+    }
+
+    /// Sets one option by name. Flags like "signals" take "true" or "false".
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "mode" => {
+                let mode = value.parse()?;
+                check_custom_wild(mode, self.rules.custom_wild_effect)?;
+                self.rules.mode = mode;
+            }
+            "custom-wild" => {
+                let effect = value.parse()?;
+                check_custom_wild(self.rules.mode, Some(effect))?;
+                self.rules.custom_wild_effect = Some(effect);
+            }
+            "teams" => self.rules.teams = Some(parse_number(key, value)?),
+            "signals" => self.rules.team_signals = parse_flag(key, value)?,
+            "last-player-standing" => self.rules.last_player_standing = parse_flag(key, value)?,
+            "humans" => self.humans = parse_count(key, value)?,
+            "ai" => self.ais = parse_count(key, value)?,
+            "names" => self.names = value.split(',').map(|name| name.trim().to_string()).filter(|name| !name.is_empty()).collect(),
+            "difficulty" => {
+                self.difficulties = value.split(',')
+                    .map(|difficulty| difficulty.trim().parse())
+                    .collect::<Result<Vec<Difficulty>, String>>()?;
+            }
+            "expert-iterations" => self.expert_iterations = parse_number(key, value)?,
+            "expert-time" => self.expert_time = Duration::from_millis(parse_number(key, value)?),
+            "duel-depth" => self.duel_depth = parse_number(key, value)?,
+            "duel-time" => self.duel_time = Duration::from_millis(parse_number(key, value)?),
+            "bot" => self.bot = Some(value.to_string()),
+            "bot-timeout" => self.bot_timeout = Duration::from_millis(parse_number(key, value)?),
+            "weights" => self.weights = value.to_string(),
+            "hint-difficulty" => self.hint_difficulty = value.parse()?,
+            "ranked" => self.ranked = parse_flag(key, value)?,
+            "pace" => self.pace = value.parse()?,
+            _ => return Err(format!("\"{}\" isn't a setting.", key)),
+        }
+        Ok(())
+    }
+
+    /// How well the `ai_number`th computer player plays, counting from 0.
+    pub fn difficulty(&self, ai_number: usize) -> Difficulty {
+        self.difficulties.get(ai_number)
+            .or(self.difficulties.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Who each seat is rated as. Humans go by their names and computer players by their
+    /// difficulty.
+    pub fn rated_names(&self, ai_seats: &[usize]) -> Vec<Option<String>> {
+        let (mut human_number, mut ai_number) = (0, 0);
+        (0..(self.humans + self.ais) as usize)
+            .map(|seat| if ai_seats.contains(&seat) {
+                ai_number += 1;
+                Some(self.rated_name(ai_number - 1))
+            } else {
+                human_number += 1;
+                self.names.get(human_number - 1).cloned()
+            })
+            .collect()
+    }
+
+    /// Who the `ai_number`th computer player is rated as. Bots go by the command that runs them,
+    /// since different bots can be very different players.
+    pub fn rated_name(&self, ai_number: usize) -> String {
+        match (self.difficulty(ai_number), &self.bot) {
+            (Difficulty::Bot, Some(bot)) => bot.clone(),
+            (difficulty, _) => difficulty.to_string(),
+        }
+    }
+
+    /// How the `ai_number`th computer player picks its moves. Fails if their bot can't be
+    /// started or their weights can't be read.
+    pub fn strategy(&self, ai_number: usize) -> Result<Box<dyn Strategy>, String> {
+        self.strategy_for(self.difficulty(ai_number))
+    }
+
+    /// What gives humans hints, unless it's a ranked game.
+    pub fn hint_strategy(&self) -> Result<Option<Box<dyn Strategy>>, String> {
+        if self.ranked {
+            return Ok(None);
+        }
+        self.strategy_for(self.hint_difficulty).map(Some)
+    }
+
+    fn strategy_for(&self, difficulty: Difficulty) -> Result<Box<dyn Strategy>, String> {
+        match difficulty {
+            Difficulty::Expert => Ok(Box::new(MctsStrategy {
+                iterations: self.expert_iterations,
+                time_limit: self.expert_time,
+            })),
+            Difficulty::Duel => Ok(Box::new(ExpectiminimaxStrategy {
+                depth: self.duel_depth,
+                time_limit: self.duel_time,
+            })),
+            Difficulty::Tuned => Ok(Box::new(LinearStrategy { weights: Weights::load(&self.weights)? })),
+            Difficulty::Bot => match &self.bot {
+                Some(bot) => Ok(Box::new(ExternalStrategy::spawn(bot, self.bot_timeout)?)),
+                None => Err("Bot players need a command to run, set with the bot setting.".to_string()),
+            },
+            difficulty => difficulty.strategy(),
+        }
+    }
+}
+
+/// Flipping and drawing a dark side color only work with a deck that has a dark side.
+fn check_custom_wild(mode: GameMode, effect: Option<Effect>) -> Result<(), String> {
+    match effect {
+        Some(effect @ (Effect::Flip | Effect::DrawColor)) if mode != GameMode::Flip => Err(format!(
+            "Custom wilds can only {} in flip mode, the other decks don't have a dark side.",
+            effect,
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn parse_number<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("{} needs a number, not \"{}\".", key, value))
+}
+
+/// Parses how many players of a kind there are, which can't be negative.
+fn parse_count(key: &str, value: &str) -> Result<i32, String> {
+    match parse_number(key, value)? {
+        count if count < 0 => Err(format!("{} can't be negative.", key)),
+        count => Ok(count),
+    }
+}
+
+fn parse_flag(key: &str, value: &str) -> Result<bool, String> {
+    value.parse().map_err(|_| format!("{} needs \"true\" or \"false\", not \"{}\".", key, value))
+}
+
+impl FromStr for Config {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut config = Config::default();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => config.set(key.trim(), value.trim())?,
+                None => return Err(format!("\"{}\" should look like \"key = value\".", line)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "mode = {}", self.rules.mode)?;
+        if let Some(effect) = self.rules.custom_wild_effect {
+            // Effect names are written with dashes wherever they're typed in.
+            writeln!(f, "custom-wild = {}", effect.to_string().replace(' ', "-"))?;
+        }
+        if let Some(teams) = self.rules.teams {
+            writeln!(f, "teams = {}", teams)?;
+        }
+        writeln!(f, "signals = {}", self.rules.team_signals)?;
+        writeln!(f, "last-player-standing = {}", self.rules.last_player_standing)?;
+        writeln!(f, "humans = {}", self.humans)?;
+        writeln!(f, "ai = {}", self.ais)?;
+        if !self.names.is_empty() {
+            writeln!(f, "names = {}", self.names.join(","))?;
+        }
+        let difficulties: Vec<String> = self.difficulties.iter().map(Difficulty::to_string).collect();
+        writeln!(f, "difficulty = {}", difficulties.join(","))?;
+        writeln!(f, "expert-iterations = {}", self.expert_iterations)?;
+        writeln!(f, "expert-time = {}", self.expert_time.as_millis())?;
+        writeln!(f, "duel-depth = {}", self.duel_depth)?;
+        writeln!(f, "duel-time = {}", self.duel_time.as_millis())?;
+        if let Some(bot) = &self.bot {
+            writeln!(f, "bot = {}", bot)?;
+        }
+        writeln!(f, "bot-timeout = {}", self.bot_timeout.as_millis())?;
+        writeln!(f, "weights = {}", self.weights)?;
+        writeln!(f, "hint-difficulty = {}", self.hint_difficulty)?;
+        writeln!(f, "ranked = {}", self.ranked)?;
+        writeln!(f, "pace = {}", self.pace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_str {
+        use super::super::*;
+        use crate::{card::Effect, game::GameMode};
+
+        #[test]
+        fn reads_every_setting() {
+            let config: Config = "
+                # A No Mercy game against a couple computer players.
+                mode = no-mercy
+                custom-wild = swap-hands
+                teams = 2
+                signals = true
+                last-player-standing = true
+                humans = 2
+                ai = 2
+                names = alice, bob
+                difficulty = easy, expert
+                expert-iterations = 200
+                expert-time = 250
+                duel-depth = 3
+                duel-time = 100
+                bot = python3 bot.py --fast
+                bot-timeout = 100
+                weights = tuned/weights.txt
+                hint-difficulty = expert
+                ranked = true
+                pace = keypress
+            ".parse().unwrap();
+
+            assert_eq!(config.rules.mode, GameMode::NoMercy);
+            assert_eq!(config.rules.custom_wild_effect, Some(Effect::SwapHands));
+            assert_eq!(config.rules.teams, Some(2));
+            assert!(config.rules.team_signals);
+            assert!(config.rules.last_player_standing);
+            assert_eq!(config.humans, 2);
+            assert_eq!(config.ais, 2);
+            assert_eq!(config.names, vec!["alice", "bob"]);
+            assert_eq!(config.difficulties, vec![Difficulty::Easy, Difficulty::Expert]);
+            assert_eq!(config.expert_iterations, 200);
+            assert_eq!(config.expert_time, Duration::from_millis(250));
+            assert_eq!(config.duel_depth, 3);
+            assert_eq!(config.duel_time, Duration::from_millis(100));
+            assert_eq!(config.bot.as_deref(), Some("python3 bot.py --fast"));
+            assert_eq!(config.bot_timeout, Duration::from_millis(100));
+            assert_eq!(config.weights, "tuned/weights.txt");
+            assert_eq!(config.hint_difficulty, Difficulty::Expert);
+            assert!(config.ranked);
+            assert_eq!(config.pace, Pace::Keypress);
+        }
+
+        #[test]
+        fn rejects_unknown_settings() {
+            assert!("speed = fast".parse::<Config>().is_err());
+            assert!("difficulty = impossible".parse::<Config>().is_err());
+            assert!("humans".parse::<Config>().is_err());
+            assert!("pace = slow".parse::<Config>().is_err());
+        }
+
+        #[test]
+        fn rejects_negative_player_counts() {
+            assert!("ai = -1".parse::<Config>().is_err());
+            assert!("humans = -2".parse::<Config>().is_err());
+            assert_eq!("ai = 0".parse::<Config>().unwrap().ais, 0);
+        }
+
+        #[test]
+        fn only_flip_games_have_wilds_that_need_a_dark_side() {
+            assert!("mode = modern\ncustom-wild = flip".parse::<Config>().is_err());
+            assert!("mode = modern\ncustom-wild = draw-color".parse::<Config>().is_err());
+            assert!("mode = flip\ncustom-wild = flip\nmode = classic".parse::<Config>().is_err());
+            assert!("mode = flip\ncustom-wild = draw-color".parse::<Config>().is_ok());
+        }
+
+        #[test]
+        fn reads_back_what_it_writes() {
+            let mut config = Config::default();
+            config.rules.mode = GameMode::Modern;
+            config.rules.custom_wild_effect = Some(Effect::Draw(3));
+            config.rules.teams = Some(2);
+            config.ais = 3;
+            config.difficulties = vec![Difficulty::Hard, Difficulty::Easy];
+            config.names = vec!["alice".to_string()];
+            config.bot = Some("./my-bot".to_string());
+            config.hint_difficulty = Difficulty::Tuned;
+            config.ranked = true;
+            config.pace = Pace::Delay(Duration::from_millis(250));
+
+            let read_back: Config = config.to_string().parse().unwrap();
+
+            assert_eq!(read_back, config);
+        }
+    }
+
+    mod difficulty {
+        use super::super::*;
+
+        #[test]
+        fn computer_players_past_the_list_use_the_last_difficulty() {
+            let config = Config { difficulties: vec![Difficulty::Easy, Difficulty::Hard], ..Config::default() };
+
+            assert_eq!(config.difficulty(0), Difficulty::Easy);
+            assert_eq!(config.difficulty(1), Difficulty::Hard);
+            assert_eq!(config.difficulty(2), Difficulty::Hard);
+        }
+    }
+
+    mod hint_strategy {
+        use super::super::*;
+
+        #[test]
+        fn ranked_games_dont_have_hints() {
+            let config = Config { ranked: true, ..Config::default() };
+
+            assert!(config.hint_strategy().unwrap().is_none());
+        }
+    }
+
+    mod rated_names {
+        use super::super::*;
+
+        #[test]
+        fn humans_go_by_name_and_computers_by_difficulty() {
+            let config = Config {
+                humans: 2,
+                ais: 2,
+                names: vec!["alice".to_string()],
+                difficulties: vec![Difficulty::Easy, Difficulty::Hard],
+                ..Config::default()
+            };
+
+            let names = config.rated_names(&[1, 3]);
+
+            assert_eq!(names, vec![Some("alice".to_string()), Some("easy".to_string()), None, Some("hard".to_string())]);
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! This mod is for solving endgames exactly. With every hand known and only a few cards left to
+//! draw, it works out whether a player can win however everyone else plays and whatever turns up
+//! when cards get drawn. It's for analyzing open hand positions and checking what search based
+//! computer players do.
+
+use std::collections::HashMap;
+
+use crate::{
+    card::Effect,
+    game::{Action, Event, Uno},
+};
+
+/// Positions with more cards than this left to draw take too long to solve.
+pub const MAX_UNSEEN_CARDS: usize = 10;
+
+/// Solves open hand positions by trying every move anyone could make and every card every draw
+/// could turn up. A player can force a win if they have a move that wins against every move the
+/// other players could make back, whatever they all draw. Teammates play together.
+///
+/// Players can keep drawing for as long as there's something to draw, so games can go on for a
+/// long time. Positions get solved for a number of moves instead, counting every play, draw, and
+/// pass by anyone, and games nobody's won by then count as not won. Uno calls and challenges are
+/// left out, as if everyone always called Uno in time. Drawing from an empty deck tries every card
+/// in the discard pile, since it all gets shuffled back in. What the solver can't see the end of
+/// counts as not winning too: a card that's played making someone draw more than the deck has,
+/// since the card gets shuffled in with the rest, or a shuffle hands card.
+#[derive(Default)]
+pub struct EndgameSolver {
+    /// What's known about each position solved so far, by who's playing for the win and
+    /// `Uno::position_hash`. Positions reached different ways only get solved once, and positions
+    /// from earlier in the same game are still there for later ones.
+    solved: HashMap<(usize, u64), Solved>,
+}
+
+/// What's known about whether a player can force a win from a position.
+#[derive(Copy, Clone)]
+struct Solved {
+    /// Whether they can force a win within `moves` moves.
+    wins: bool,
+    /// A win within this many moves is a win within any more, and not being able to win within
+    /// this many means they can't within any fewer either.
+    moves: usize,
+}
+
+impl EndgameSolver {
+    /// Whether whoever's turn it is in `state` can force a win within `moves` moves. Nothing if
+    /// there are more than `MAX_UNSEEN_CARDS` cards left to draw.
+    pub fn can_force_win(&mut self, state: &Uno, moves: usize) -> Option<bool> {
+        if state.deck().len() > MAX_UNSEEN_CARDS {
+            return None;
+        }
+        Some(self.wins(state, state.current_player(), moves))
+    }
+
+    /// Every move whoever's turn it is in `state` could make that still lets them force a win
+    /// within `moves` moves, counting that one. It's empty when they can't, and nothing if there
+    /// are more than `MAX_UNSEEN_CARDS` cards left to draw.
+    pub fn winning_moves(&mut self, state: &Uno, moves: usize) -> Option<Vec<Action>> {
+        if state.deck().len() > MAX_UNSEEN_CARDS {
+            return None;
+        }
+        if moves == 0 {
+            return Some(Vec::new());
+        }
+        let player = state.current_player();
+        Some(
+            possible_moves(state).into_iter()
+                .filter(|action| self.wins_after(state, *action, player, moves - 1, &mut Vec::new()))
+                .collect(),
+        )
+    }
+
+    /// How many positions have been solved so far.
+    pub fn positions_solved(&self) -> usize {
+        self.solved.len()
+    }
+
+    /// Whether `player` can force a win from `state` within `moves` moves.
+    fn wins(&mut self, state: &Uno, player: usize, moves: usize) -> bool {
+        if state.game_over() {
+            return state.winning_player_index().is_some_and(|winner| same_side(state, player, winner as usize));
+        }
+        if moves == 0 {
+            return false;
+        }
+        let key = (player, state.position_hash());
+        if let Some(solved) = self.solved.get(&key) {
+            if (solved.wins && solved.moves <= moves) || (!solved.wins && solved.moves >= moves) {
+                return solved.wins;
+            }
+        }
+        let possible_moves = possible_moves(state);
+        let wins = if same_side(state, player, state.current_player()) {
+            possible_moves.into_iter().any(|action| self.wins_after(state, action, player, moves - 1, &mut Vec::new()))
+        } else {
+            // A game nobody can move in never ends, so nobody wins it.
+            !possible_moves.is_empty()
+                && possible_moves.into_iter().all(|action| self.wins_after(state, action, player, moves - 1, &mut Vec::new()))
+        };
+        self.solved.insert(key, Solved { wins, moves });
+        wins
+    }
+
+    /// Whether `player` can force a win within `moves` moves once `action` is made in `state`.
+    /// `drawn` is where in the deck the cards being tried as the next ones drawn are. When `action`
+    /// draws more cards than that, every different card that could come next gets tried too, and
+    /// they all have to win. Trying them one at a time means draws that keep going until a color
+    /// turns up get solved right too.
+    fn wins_after(&mut self, state: &Uno, action: Action, player: usize, moves: usize, drawn: &mut Vec<usize>) -> bool {
+        if let Action::Play { card_index, .. } = action {
+            let view = state.view(state.current_player());
+            if view.rules.card_effects(&view.hand[card_index]).contains(&Effect::ShuffleHands) {
+                return false;
+            }
+        }
+        let mut next = state.copy_position();
+        next.deal_next(drawn);
+        next.apply_action(next.current_player(), action).expect("legal actions can be applied");
+        // Copies start with an empty history, so everything in it happened just now.
+        let cards_drawn: usize = next.view(player).history.iter()
+            .map(|event| match event {
+                Event::Drew { count, .. } => *count,
+                _ => 0,
+            })
+            .sum();
+        let deck = state.deck();
+        if cards_drawn > deck.len() {
+            if matches!(action, Action::Play { .. }) {
+                return false;
+            }
+            // The discard pile gets shuffled into the deck before anything's drawn. It goes after
+            // the cards already there, so `drawn` still points at the same cards.
+            let mut collected = state.copy_position();
+            collected.collect_discard();
+            return self.wins_after(&collected, action, player, moves, drawn);
+        }
+        if cards_drawn <= drawn.len() {
+            return self.wins(&next, player, moves);
+        }
+
+        let mut tried: Vec<usize> = Vec::new();
+        for deck_index in 0..deck.len() {
+            let card = &deck[deck_index];
+            if drawn.contains(&deck_index) || tried.iter().any(|other_index| deck[*other_index].is_same_card_as(card)) {
+                continue;
+            }
+            tried.push(deck_index);
+            drawn.push(deck_index);
+            let wins = self.wins_after(state, action, player, moves, drawn);
+            drawn.pop();
+            if !wins {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Everything whoever's turn it is could do that moves the game along.
+fn possible_moves(state: &Uno) -> Vec<Action> {
+    state.legal_actions(state.current_player()).into_iter()
+        .filter(|action| matches!(action, Action::Play { .. } | Action::Draw | Action::Pass))
+        .collect()
+}
+
+/// Whether `other` winning counts as a win for `player`.
+fn same_side(state: &Uno, player: usize, other: usize) -> bool {
+    other == player || state.view(player).is_teammate(other)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        card::Card,
+        game::{TurnOrder, Uno},
+    };
+
+    /// A two player game where it's the first player's turn, with `top_card` on the discard pile.
+    fn position(hands: [&[&str]; 2], top_card: &str, deck: &[&str]) -> Uno {
+        let uno = Uno::new(0, 2);
+        let mut view = uno.view(0);
+        view.top_card = Some(Card::from(top_card));
+        view.turn_order = TurnOrder::Forward;
+        let hands = hands.iter().map(|hand| hand.iter().map(|card| Card::from(*card)).collect()).collect();
+        let deck = deck.iter().map(|card| Card::from(*card)).collect();
+        Uno::from_position(&view, hands, deck, Vec::new())
+    }
+
+    mod can_force_win {
+        use super::{super::*, position};
+
+        #[test]
+        fn wins_when_the_last_card_can_be_played() {
+            let state = position([&["red 5"], &["red 6"]], "red 3", &[]);
+
+            assert_eq!(EndgameSolver::default().can_force_win(&state, 1), Some(true));
+        }
+
+        #[test]
+        fn needs_enough_moves_to_get_there() {
+            // The skip, then the red 5.
+            let state = position([&["red skip", "red 5"], &["red 1"]], "red 3", &["blue 1"]);
+            let mut solver = EndgameSolver::default();
+
+            assert_eq!(solver.can_force_win(&state, 1), Some(false));
+            assert_eq!(solver.can_force_win(&state, 2), Some(true));
+        }
+
+        #[test]
+        fn a_draw_only_wins_if_every_card_it_could_turn_up_does() {
+            // After the skip, drawing the blue skip lets the first player go again and play the
+            // blue 7. Drawing the green 4 doesn't.
+            let hands: [&[&str]; 2] = [&["red skip", "blue 7"], &["green 1"]];
+            let mut solver = EndgameSolver::default();
+
+            assert_eq!(solver.can_force_win(&position(hands, "red 3", &["blue skip", "blue skip"]), 4), Some(true));
+            assert_eq!(solver.can_force_win(&position(hands, "red 3", &["blue skip", "green 4"]), 4), Some(false));
+        }
+
+        #[test]
+        fn gives_up_when_too_many_cards_are_left_to_draw() {
+            let deck = ["red 1"; MAX_UNSEEN_CARDS + 1];
+
+            assert_eq!(EndgameSolver::default().can_force_win(&position([&["red 5"], &["red 6"]], "red 3", &deck), 10), None);
+        }
+
+        #[test]
+        fn remembers_positions_it_has_solved() {
+            let state = position([&["red draw2", "red 5", "blue 4"], &["red 1", "green 3"]], "red 3", &["blue 1", "green 2", "yellow 3"]);
+            let mut solver = EndgameSolver::default();
+            let answer = solver.can_force_win(&state, 8);
+            let positions_solved = solver.positions_solved();
+
+            assert_eq!(solver.can_force_win(&state, 8), answer);
+            assert_eq!(solver.positions_solved(), positions_solved);
+        }
+    }
+
+    mod winning_moves {
+        use super::{super::*, position};
+        use crate::ai::{ExpectiminimaxStrategy, Strategy};
+
+        #[test]
+        fn finds_the_only_order_that_wins() {
+            // Playing the red 5 first lets the second player go out with their red 1.
+            let state = position([&["red skip", "red 5"], &["red 1"]], "red 3", &["blue 1", "green 2", "yellow 3"]);
+
+            let winning_moves = EndgameSolver::default().winning_moves(&state, 10).unwrap();
+
+            assert_eq!(winning_moves, vec![Action::Play { card_index: 0, color: None, swap_with: None }]);
+        }
+
+        #[test]
+        fn duel_players_take_forced_wins() {
+            let mut state = position([&["red skip", "red 5"], &["red 1"]], "red 3", &["blue 1", "green 2", "yellow 3"]);
+            state.apply_action(0, Action::CallUno).unwrap();
+            let winning_moves = EndgameSolver::default().winning_moves(&state, 10).unwrap();
+
+            let action = ExpectiminimaxStrategy::default().choose_action(&state.view(0));
+
+            assert!(winning_moves.contains(&action), "{:?} doesn't force a win", action);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! This mod is for training computer players, by letting code play a seat one action at a time
+//! against the built in computer players.
+
+use crate::{
+    ai::Difficulty,
+    card::{Card, CardFace, Color},
+    config::Config,
+    game::{Action, PlayerStatus, Uno},
+    view::PlayerView,
+};
+
+/// The seat the agent being trained plays from.
+pub const AGENT_SEAT: usize = 0;
+/// Colors a wild can be given, whichever side is showing.
+const WILD_COLORS: usize = 4;
+/// Every color on either side of a card.
+const COLORS: [Color; 8] = [
+    Color::Red, Color::Blue, Color::Yellow, Color::Green, Color::Pink, Color::Teal, Color::Orange, Color::Purple,
+];
+
+/// What the agent sees before each action. Both parts are the same size every time for a given
+/// environment.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Observation {
+    /// The agent's view of the game as numbers. In order: how many of each kind of card they hold,
+    /// which kind of card is on top and its color, the cards left to draw and any pending draw
+    /// penalty, each opponent's hand size and whether they're still in going around the table
+    /// from the agent, and whether the agent has drawn, called Uno, or can challenge someone.
+    pub features: Vec<f32>,
+    /// Which of the environment's actions are allowed right now.
+    pub action_mask: Vec<bool>,
+}
+
+/// What happened after an action.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Step {
+    pub observation: Observation,
+    /// 1 for winning the game (or being on the winning team), -1 for losing, and 0 until it's
+    /// over.
+    pub reward: f32,
+    pub done: bool,
+}
+
+/// A game where the agent plays one seat and the computer players from a config play the rest.
+/// Actions are numbered so there's the same number of them every turn. There's one for each kind
+/// of card to play, times each color for wilds, then drawing, passing, calling Uno, and
+/// challenging whoever can be challenged. Signaling teammates isn't one of them.
+pub struct Environment {
+    config: Config,
+    uno: Uno,
+    /// Every kind of card face in the deck, which is what card actions and features count by.
+    card_kinds: Vec<CardFace>,
+}
+
+impl Environment {
+    /// Sets up an environment against the `config.ais` computer players in `config`. Bots run by
+    /// other programs can't be played against, since they could stop answering. Fails if a tuned
+    /// computer player's weights can't be read.
+    pub fn new(config: Config) -> Result<Environment, String> {
+        if config.ais < 1 {
+            return Err("There has to be at least 1 computer player to play against.".to_string());
+        }
+        if (0..config.ais as usize).any(|ai_number| config.difficulty(ai_number) == Difficulty::Bot) {
+            return Err("Bots can't be trained against.".to_string());
+        }
+        for ai_number in 0..config.ais as usize {
+            config.strategy(ai_number)?;
+        }
+        let mut card_kinds: Vec<CardFace> = Vec::new();
+        for card in config.rules.mode.deck() {
+            for face in [Some(card.face()), card.flip_side].into_iter().flatten() {
+                if !card_kinds.contains(&face) {
+                    card_kinds.push(face);
+                }
+            }
+        }
+        let uno = Uno::seeded(1, config.ais, config.rules, 0);
+        Ok(Environment { config, uno, card_kinds })
+    }
+
+    /// How many actions there are, which is the length of every action mask.
+    pub fn action_count(&self) -> usize {
+        self.first_other_action() + 4
+    }
+
+    /// How many features every observation has.
+    pub fn observation_size(&self) -> usize {
+        let opponents = self.config.ais as usize;
+        2 * self.card_kinds.len() + COLORS.len() + 2 + 2 * opponents + 3
+    }
+
+    /// Starts a new game dealt from `seed`. Against computer players that don't pick moves at
+    /// random, the same seed and actions play out the same way every time.
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.uno = Uno::seeded(1, self.config.ais, self.config.rules, seed);
+        for (ai_number, seat) in self.uno.ai_seats().into_iter().enumerate() {
+            let strategy = self.config.strategy(ai_number).expect("strategies were checked when the environment was set up");
+            self.uno.set_strategy(seat, strategy);
+        }
+        self.let_opponents_play();
+        self.observation()
+    }
+
+    /// Takes the action numbered `action` for the agent, then lets the computer players go until
+    /// it's the agent's turn again or the game is over.
+    pub fn step(&mut self, action: usize) -> Result<Step, String> {
+        if self.uno.game_over() {
+            return Err("The game is over, it needs to be reset.".to_string());
+        }
+        let view = self.uno.view(AGENT_SEAT);
+        let game_action = self.decode(&view, action)
+            .filter(|_| self.action_mask(&view)[action])
+            .ok_or_else(|| format!("Action {} isn't allowed right now.", action))?;
+        self.uno.apply_action(AGENT_SEAT, game_action)?;
+        self.let_opponents_play();
+
+        let done = self.uno.game_over();
+        let reward = match self.uno.winning_player_index() {
+            Some(winner) if winner as usize == AGENT_SEAT || self.uno.view(AGENT_SEAT).is_teammate(winner as usize) => 1.0,
+            Some(_) => -1.0,
+            None => 0.0,
+        };
+        Ok(Step { observation: self.observation(), reward, done })
+    }
+
+    fn let_opponents_play(&mut self) {
+        while !self.uno.game_over() && self.uno.current_player() != AGENT_SEAT {
+            self.uno.automate_current_player_turn();
+        }
+    }
+
+    fn observation(&self) -> Observation {
+        let view = self.uno.view(AGENT_SEAT);
+        Observation { features: self.features(&view), action_mask: self.action_mask(&view) }
+    }
+
+    fn features(&self, view: &PlayerView) -> Vec<f32> {
+        let mut features = vec![0.0; self.card_kinds.len()];
+        for card in &view.hand {
+            features[self.card_kind(card)] += 1.0;
+        }
+        let mut top_kind = vec![0.0; self.card_kinds.len()];
+        let mut top_color = vec![0.0; COLORS.len()];
+        if let Some(top_card) = &view.top_card {
+            top_kind[self.card_kind(top_card)] = 1.0;
+            if let Some(color) = top_card.color {
+                top_color[COLORS.iter().position(|other_color| *other_color == color).unwrap()] = 1.0;
+            }
+        }
+        features.extend(top_kind);
+        features.extend(top_color);
+        features.push(view.deck_size as f32 / self.config.rules.mode.deck().len() as f32);
+        features.push(view.pending_draw as f32 / 10.0);
+        let players = view.hand_sizes.len();
+        for offset in 1..players {
+            let seat = (view.seat + offset) % players;
+            features.push(view.hand_sizes[seat] as f32 / 10.0);
+            features.push(if view.statuses[seat] == PlayerStatus::Playing { 1.0 } else { 0.0 });
+        }
+        let can_challenge = view.legal_actions().iter().any(|action| matches!(action, Action::Challenge(_)));
+        for flag in [view.drew_this_turn, view.called_uno, can_challenge] {
+            features.push(if flag { 1.0 } else { 0.0 });
+        }
+        features
+    }
+
+    fn action_mask(&self, view: &PlayerView) -> Vec<bool> {
+        let mut action_mask = vec![false; self.action_count()];
+        for action in view.legal_actions() {
+            if let Some(index) = self.encode(view, &action) {
+                action_mask[index] = true;
+            }
+        }
+        action_mask
+    }
+
+    /// Which kind of card `card` is, going by the face that's up.
+    fn card_kind(&self, card: &Card) -> usize {
+        let mut face = card.face();
+        if face.wild {
+            face.color = None;
+        }
+        self.card_kinds.iter().position(|kind| *kind == face).expect("every card comes from the deck")
+    }
+
+    /// Where each kind of card's actions start. Wilds get one action for each color.
+    fn first_card_action(&self, kind: usize) -> usize {
+        self.card_kinds[..kind].iter().map(|kind| if kind.wild { WILD_COLORS } else { 1 }).sum()
+    }
+
+    /// Where the actions that aren't playing a card start.
+    fn first_other_action(&self) -> usize {
+        self.first_card_action(self.card_kinds.len())
+    }
+
+    fn encode(&self, view: &PlayerView, action: &Action) -> Option<usize> {
+        let other_actions = self.first_other_action();
+        match *action {
+            Action::Play { card_index, color, .. } => {
+                let kind = self.card_kind(&view.hand[card_index]);
+                let color_index = color.map_or(0, |color| Color::for_side(view.side).iter().position(|side_color| *side_color == color).unwrap());
+                Some(self.first_card_action(kind) + color_index)
+            }
+            Action::Draw => Some(other_actions),
+            Action::Pass => Some(other_actions + 1),
+            Action::CallUno => Some(other_actions + 2),
+            Action::Challenge(_) => Some(other_actions + 3),
+            Action::Signal(_) => None,
+        }
+    }
+
+    fn decode(&self, view: &PlayerView, action: usize) -> Option<Action> {
+        let other_actions = self.first_other_action();
+        if action >= other_actions {
+            return match action - other_actions {
+                0 => Some(Action::Draw),
+                1 => Some(Action::Pass),
+                2 => Some(Action::CallUno),
+                3 => (0..view.missed_uno_calls.len())
+                    .find(|target| *target != view.seat && view.missed_uno_calls[*target])
+                    .map(Action::Challenge),
+                _ => None,
+            };
+        }
+        let kind = (0..self.card_kinds.len()).rev().find(|kind| self.first_card_action(*kind) <= action)?;
+        let card_index = view.hand.iter().position(|card| self.card_kind(card) == kind)?;
+        let color = self.card_kinds[kind].wild.then(|| Color::for_side(view.side)[action - self.first_card_action(kind)]);
+        Some(Action::Play { card_index, color, swap_with: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod environment {
+        use super::super::*;
+        use crate::game::GameMode;
+
+        fn environment(mode: GameMode) -> Environment {
+            let mut config = Config::default();
+            config.rules.mode = mode;
+            config.ais = 2;
+            Environment::new(config).unwrap()
+        }
+
+        /// Plays the first allowed action until the game's over, giving back the last step.
+        fn play_out(environment: &mut Environment, mut observation: Observation) -> Step {
+            loop {
+                let action = observation.action_mask.iter().position(|allowed| *allowed).unwrap();
+                let step = environment.step(action).unwrap();
+                assert_eq!(step.observation.features.len(), environment.observation_size());
+                assert_eq!(step.observation.action_mask.len(), environment.action_count());
+                if step.done {
+                    return step;
+                }
+                observation = step.observation;
+            }
+        }
+
+        #[test]
+        fn the_same_seed_deals_the_same_game() {
+            let mut environment = environment(GameMode::Classic);
+
+            let first = environment.reset(7);
+            let second = environment.reset(7);
+
+            assert_eq!(first, second);
+            assert_ne!(environment.reset(8), first);
+        }
+
+        #[test]
+        fn games_can_be_played_to_the_end() {
+            for mode in [GameMode::Classic, GameMode::Flip, GameMode::NoMercy, GameMode::Modern] {
+                let mut environment = environment(mode);
+                let observation = environment.reset(3);
+
+                let step = play_out(&mut environment, observation);
+
+                assert!(step.reward == 1.0 || step.reward == -1.0);
+                assert!(environment.step(0).is_err());
+            }
+        }
+
+        #[test]
+        fn the_same_seed_and_actions_play_out_the_same() {
+            let mut environment = environment(GameMode::Classic);
+
+            let observation = environment.reset(11);
+            let first = play_out(&mut environment, observation);
+            let observation = environment.reset(11);
+            let second = play_out(&mut environment, observation);
+
+            assert_eq!(first, second);
+        }
+
+        #[test]
+        fn actions_that_arent_allowed_are_refused() {
+            let mut environment = environment(GameMode::Classic);
+            let observation = environment.reset(5);
+
+            let not_allowed = observation.action_mask.iter().position(|allowed| !*allowed).unwrap();
+
+            assert!(environment.step(not_allowed).is_err());
+        }
+
+        #[test]
+        fn allowed_actions_turn_back_into_legal_ones() {
+            for seed in 0..20 {
+                let mut environment = environment(GameMode::Flip);
+                let observation = environment.reset(seed);
+                let view = environment.uno.view(AGENT_SEAT);
+                let legal_actions = view.legal_actions();
+
+                for (index, _) in observation.action_mask.iter().enumerate().filter(|(_, allowed)| **allowed) {
+                    let action = environment.decode(&view, index).unwrap();
+                    assert_eq!(environment.encode(&view, &action), Some(index));
+                    assert!(legal_actions.iter().any(|legal_action| match (legal_action, action) {
+                        (Action::Play { card_index, color, .. }, Action::Play { card_index: other_index, color: other_color, .. }) =>
+                            environment.card_kind(&view.hand[*card_index]) == environment.card_kind(&view.hand[other_index]) && *color == other_color,
+                        _ => *legal_action == action,
+                    }));
+                }
+            }
+        }
+
+        #[test]
+        fn bots_cant_be_trained_against() {
+            let config = Config { difficulties: vec![Difficulty::Bot], ..Config::default() };
+
+            assert!(Environment::new(config).is_err());
+        }
+    }
+}
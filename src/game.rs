@@ -1,28 +1,215 @@
-use rand::{seq::SliceRandom, thread_rng};
+//! This mod is for game rules.
+
+use std::{collections::hash_map::DefaultHasher, fmt::Display, hash::{Hash, Hasher}, str::FromStr};
+
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    card::{Card, Color, DrawEffect, TurnEffect},
-    user_input::Input, ui::{UI, PlayerInstruction, TurnRecap},
+    ai::{describe_action, BasicStrategy, Explanation, Strategy},
+    card::{Card, Color, DrawEffect, Effect, Side, TurnEffect},
+    profile::Profile,
+    user_input::{wait_for_next_move, Input}, ui::{Pace, UI, PlayerInstruction, TurnRecap},
+    view::PlayerView,
 };
 
-/**
- This mod is for game rules.
-*/
-
 pub struct Uno {
     players: Vec<Player>,
     current_player_index: i32,
     deck: Vec<Card>,
     discard: Vec<Card>,
     wild_card_index_to_pick_color_for: Option<i32>,
+    /// Set once a human has picked a color for a swap hands wild and still needs to pick who to
+    /// swap with.
+    swap_hands_wild_color: Option<Color>,
     turn_order: TurnOrder,
+    rules: Rules,
+    side: Side,
+    /// Cards the current player has to draw unless they stack another draw card on top.
+    pending_draw: i32,
+    /// Players who ran out of cards in a last player standing game, in the order they went out.
+    finished_players: Vec<i32>,
+    /// Players knocked out by the mercy rule, in the order they were knocked out.
+    knocked_out_players: Vec<i32>,
+    history: Vec<Event>,
+    /// How each player has played this game, for adding to their profile once it's over.
+    profiles: Vec<Profile>,
+    /// How each player played in earlier games, by seat. Empty when nobody's been seen before.
+    past_profiles: Vec<Profile>,
+    /// Where in the history each human's last move started, by seat, so they can be caught up on
+    /// everything since.
+    last_moves: Vec<usize>,
+    /// Why computer players made each of their moves since a human last did anything, by seat.
+    explanations: Vec<(usize, Explanation)>,
+    /// What recommends moves to humans who ask for a hint. Hints are off without one.
+    hint_strategy: Option<Box<dyn Strategy>>,
+    /// How computer players' moves are shown to humans.
+    pace: Pace,
+    /// Shuffles the cards. Seeded games deal and shuffle the same way every time.
+    rng: StdRng,
     ui: UI,
 }
 
+/// Something a player can do on their turn. Humans and computer players both act through these.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Plays a card from the player's hand. Wilds need a color from the side that's showing. Swap
+    /// hands cards swap with `swap_with` if it's set, otherwise with whoever has the fewest cards.
+    Play { card_index: usize, color: Option<Color>, swap_with: Option<usize> },
+    /// Draws a card, or takes the stacked up draw penalty if there is one.
+    Draw,
+    /// Ends the turn without playing. Only allowed after drawing a card.
+    Pass,
+    /// Says "Uno!" while holding two cards, before playing the second to last one.
+    CallUno,
+    /// Catches a player who got down to one card without calling Uno, who then draws two cards.
+    /// Can be done on anyone's turn, until the caught player's next turn.
+    Challenge(usize),
+    /// Signals a color to the player's team. Doesn't end their turn.
+    Signal(Color),
+}
+
+/// Something that happened in the game. Everyone at the table sees these.
+#[derive(Copy, Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    /// A player played a card. Wilds have the color that was picked for them.
+    Played { player: usize, card: Card },
+    /// A player drew cards. They're forced to when someone else's card makes them draw, otherwise
+    /// they chose to, which usually means they had nothing to play.
+    Drew { player: usize, count: usize, forced: bool },
+    /// A card a player got rid of without playing it, like with a discard all card.
+    Discarded { player: usize, card: Card },
+    /// A player caught `target` not calling Uno.
+    Challenged { player: usize, target: usize },
+    /// A player lost their turn to someone else's card.
+    Skipped { player: usize },
+}
+
+/// Which set of cards and card effects a game is played with.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    #[default]
+    Classic,
+    /// Uno Flip: two faced cards and a flip card that turns the whole game over to its dark side.
+    Flip,
+    /// Uno No Mercy: brutal action cards, draw cards always stack, and players holding too many
+    /// cards get knocked out.
+    NoMercy,
+    /// The classic deck plus the wild shuffle hands and blank customizable wilds from newer decks.
+    Modern,
+}
+
+impl GameMode {
+    /// Every card in the game, unshuffled.
+    pub fn deck(&self) -> Vec<Card> {
+        match self {
+            GameMode::Classic => create_deck(),
+            GameMode::Flip => create_flip_deck(),
+            GameMode::NoMercy => create_no_mercy_deck(),
+            GameMode::Modern => create_modern_deck(),
+        }
+    }
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameMode::Classic => write!(f, "classic"),
+            GameMode::Flip => write!(f, "flip"),
+            GameMode::NoMercy => write!(f, "no-mercy"),
+            GameMode::Modern => write!(f, "modern"),
+        }
+    }
+}
+
+impl FromStr for GameMode {
+    type Err = String;
+
+    fn from_str(mode_name: &str) -> Result<Self, Self::Err> {
+        match mode_name {
+            "classic" => Ok(GameMode::Classic),
+            "flip" => Ok(GameMode::Flip),
+            "no-mercy" => Ok(GameMode::NoMercy),
+            "modern" => Ok(GameMode::Modern),
+            _ => Err("The mode must be one of \"classic\", \"flip\", \"no-mercy\", or \"modern\".".to_string()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Serialize)]
+pub struct Rules {
+    pub mode: GameMode,
+    /// What blank customizable wilds do. They're plain wilds when this isn't set.
+    pub custom_wild_effect: Option<Effect>,
+    /// Splits the players into this many teams. Teammates sit evenly spread around the table, so
+    /// with two teams of two partners sit across from each other.
+    pub teams: Option<i32>,
+    /// Lets teammates signal a color to each other, once per turn.
+    pub team_signals: bool,
+    /// Instead of the first player out winning, players who run out of cards leave the game and
+    /// play goes on until only one player is left holding cards.
+    pub last_player_standing: bool,
+}
+
+impl Rules {
+    /// Why `players` players can't be split into teams, if they can't.
+    pub fn check_teams(&self, players: i32) -> Result<(), String> {
+        match self.teams {
+            Some(teams) if teams < 2 || players % teams != 0 => {
+                Err(format!("{} players can't be split evenly into {} teams.", players, teams))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a player hit by a draw card can pass the penalty on by playing a draw card of equal
+    /// or higher value.
+    pub fn stacking(&self) -> bool {
+        self.mode == GameMode::NoMercy
+    }
+
+    /// Players holding at least this many cards are knocked out of the game.
+    pub fn mercy_limit(&self) -> Option<usize> {
+        match self.mode {
+            GameMode::NoMercy => Some(25),
+            _ => None,
+        }
+    }
+
+    /// The effects of a card, including whatever the players decided customizable cards do.
+    pub fn card_effects(&self, card: &Card) -> Vec<Effect> {
+        let mut effects = card.effects();
+        if card.turn_effect == Some(TurnEffect::Custom) {
+            effects.extend(self.custom_wild_effect);
+        }
+        effects
+    }
+}
+
 impl Uno {
     pub fn new(player_count: i32, ai_count: i32) -> Self {
-        if player_count == 0 {
-            panic!("Only Uno games with at least 1 human player are supported.");
+        Uno::with_rules(player_count, ai_count, Rules::default())
+    }
+
+    pub fn with_rules(player_count: i32, ai_count: i32, rules: Rules) -> Self {
+        Uno::with_rng(player_count, ai_count, rules, unseeded_rng())
+    }
+
+    /// A game that's dealt and shuffled the same way every time it's given the same `seed`. Whether
+    /// it plays out the same way is up to the strategies, since some of them pick moves at random.
+    pub fn seeded(player_count: i32, ai_count: i32, rules: Rules, seed: u64) -> Self {
+        Uno::with_rng(player_count, ai_count, rules, StdRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(player_count: i32, ai_count: i32, rules: Rules, rng: StdRng) -> Self {
+        if player_count + ai_count < 2 {
+            panic!("Uno needs at least 2 players.");
+        }
+        if let Err(error) = rules.check_teams(player_count + ai_count) {
+            panic!("{}", error);
         }
 
         let mut game = Uno {
@@ -31,25 +218,41 @@ impl Uno {
             deck: Vec::new(),
             discard: Vec::new(),
             wild_card_index_to_pick_color_for: None,
+            swap_hands_wild_color: None,
             turn_order: TurnOrder::Forward,
+            rules,
+            side: Side::Light,
+            pending_draw: 0,
+            finished_players: Vec::new(),
+            knocked_out_players: Vec::new(),
+            history: Vec::new(),
+            profiles: Vec::new(),
+            past_profiles: Vec::new(),
+            last_moves: Vec::new(),
+            explanations: Vec::new(),
+            hint_strategy: None,
+            pace: Pace::default(),
+            rng,
             ui: UI::default()
         };
 
-        game.deck = create_deck();
-        game.deck.shuffle(&mut thread_rng());
+        game.deck = rules.mode.deck();
+        game.deck.shuffle(&mut game.rng);
 
         let mut human_players: Vec<Player> = Vec::new();
         for _ in 0..player_count {
             let mut player = Player::default();
-            draw_cards(&mut player.hand, 7, &mut game.deck, &mut game.discard);
+            draw_cards(&mut player.hand, 7, &mut game.deck, &mut game.discard, &mut game.rng);
             human_players.push(player);
         }
 
         let mut ai_players: Vec<Player> = Vec::new();
         for _ in 0..ai_count {
-            let mut player = Player::default();
-            player.ai = true;
-            draw_cards(&mut player.hand, 7, &mut game.deck, &mut game.discard);
+            let mut player = Player {
+                strategy: Some(Box::new(BasicStrategy)),
+                ..Player::default()
+            };
+            draw_cards(&mut player.hand, 7, &mut game.deck, &mut game.discard, &mut game.rng);
             ai_players.push(player);
         }
 
@@ -65,8 +268,25 @@ impl Uno {
             }
         }
 
-        game.ui.display_hand(1, &game.players.first().unwrap().hand);
+        game.profiles = vec![Profile::default(); game.players.len()];
+
+        if let Some(teams) = rules.teams {
+            for (index, player) in game.players.iter_mut().enumerate() {
+                player.team = Some(index as i32 % teams);
+            }
+            game.ui.teams = Some(game.players.iter().map(|player| player.team.unwrap() + 1).collect());
+            game.ui.team_signals = rules.team_signals;
+        }
+
+        let PlayerView { hand, backs, .. } = game.view(0);
+        game.ui.display_hand(1, &hand, &backs);
         game.ui.player_instruction = Some(PlayerInstruction::PickCard);
+        if rules.mode == GameMode::Flip {
+            game.ui.side = Some(game.side);
+        }
+        if rules.mode == GameMode::Modern {
+            game.ui.custom_wild_effect = rules.custom_wild_effect;
+        }
 
         game
     }
@@ -74,21 +294,43 @@ impl Uno {
     pub fn input(&mut self, input: Input) {
         // Create a valid game command from raw user input.
         let command: Option<Command> = match input {
-            Input::Number(card_index) => {
-                Some(Command::PickCardToPlay(card_index))
+            Input::Number(number) => {
+                if self.swap_hands_wild_color.is_some() {
+                    Some(Command::PickPlayerToSwapWith(number))
+                } else {
+                    Some(Command::PickCardToPlay(number))
+                }
             }
             Input::Text(input_text) => {
-                if input_text.to_lowercase().as_str() == "d" {
+                let input_text = input_text.to_lowercase();
+                if input_text.as_str() == "d" {
                     Some(Command::DrawCard)
+                } else if input_text.as_str() == "e" {
+                    Some(Command::Explain)
+                } else if input_text.as_str() == "h" {
+                    Some(Command::Hint)
+                } else if input_text.as_str() == "p" {
+                    Some(Command::Pass)
+                } else if input_text.as_str() == "u" {
+                    Some(Command::CallUno)
+                } else if let Some(player_number) = input_text.strip_prefix("c ") {
+                    player_number.trim().parse::<usize>().ok()
+                        .filter(|player_number| *player_number >= 1)
+                        .map(|player_number| Command::Challenge(player_number - 1))
+                } else if let Some(signaled_color) = input_text.strip_prefix("s ") {
+                    if self.rules.team_signals && self.rules.teams.is_some() {
+                        // Only colors from the side that's showing make sense to signal.
+                        parse_color(signaled_color)
+                            .filter(|color| color.side() == self.side)
+                            .map(Command::SignalColor)
+                    } else {
+                        None
+                    }
                 } else if self.wild_card_index_to_pick_color_for.is_some() {
-                    let picked_wild_color: Option<Color> = match input_text.to_lowercase().as_str() {
-                        "r" => Some(Color::Red),
-                        "b" => Some(Color::Blue),
-                        "g" => Some(Color::Green),
-                        "y" => Some(Color::Yellow),
-                        _ => None,
-                    };
-                    picked_wild_color.and_then(|color| Some(Command::PickWildCardColor(color)))
+                    // Wilds can only be given a color from the side that's showing.
+                    parse_color(&input_text)
+                        .filter(|color| color.side() == self.side)
+                        .map(Command::PickWildCardColor)
                 } else {
                     None
                 }
@@ -97,79 +339,150 @@ impl Uno {
 
         // Process command
         if let Some(command) = command {
+            let history_start = self.history.len();
+            let player_index = self.current_player_index;
+            if command != Command::Explain {
+                self.ui.explanations.clear();
+            }
+            if command != Command::Hint {
+                self.ui.hint = None;
+            }
             match command {
+                Command::Hint => match self.hint() {
+                    Ok((action, explanation)) => {
+                        let card_index = match action {
+                            Action::Play { card_index, .. } => Some(card_index),
+                            _ => None,
+                        };
+                        self.ui.hint = Some((card_index, explanation));
+                        self.ui.error = None;
+                    }
+                    Err(error) => self.ui.error = Some(error),
+                },
+                Command::Explain => {
+                    self.ui.explanations = self.explanations.iter()
+                        .map(|(seat, explanation)| (*seat as i32 + 1, explanation.clone()))
+                        .collect();
+                    self.ui.error = self.explanations.is_empty()
+                        .then(|| "The computer players haven't moved since you last did.".to_string());
+                },
+                Command::SignalColor(color) => {
+                    self.ui.error = self.apply_action(self.current_player_index as usize, Action::Signal(color)).err();
+                },
+                Command::Pass => {
+                    self.ui.error = self.apply_action(self.current_player_index as usize, Action::Pass).err();
+                },
+                Command::CallUno => {
+                    self.ui.error = self.apply_action(self.current_player_index as usize, Action::CallUno).err();
+                },
+                Command::Challenge(target) => {
+                    self.ui.error = self.apply_action(self.current_player_index as usize, Action::Challenge(target)).err();
+                },
                 Command::DrawCard => {
-                    let current_player = &mut self.players[self.current_player_index as usize];
-                    draw_cards(
-                        &mut current_player.hand,
-                        1,
-                        &mut self.deck,
-                        &mut self.discard,
-                    );
-                    self.ui.display_hand(self.current_player_index + 1, &current_player.hand);
+                    self.wild_card_index_to_pick_color_for = None;
+                    self.swap_hands_wild_color = None;
+                    self.ui.error = self.apply_action(self.current_player_index as usize, Action::Draw).err();
                 },
                 Command::PickWildCardColor(wild_color) => {
-                    let current_player = &mut self.players[self.current_player_index as usize];
                     if let Some(wild_index) = self.wild_card_index_to_pick_color_for {
-                        let mut wild_card = current_player.hand.remove((wild_index - 1) as usize);
-                        wild_card.color = Some(wild_color);
-                        self.ui.player_instruction = None;
-                        self.wild_card_index_to_pick_color_for = None;
-                        self.play_card(wild_card);
+                        let wild_card = self.players[self.current_player_index as usize].hand[(wild_index - 1) as usize];
+                        let players_still_in = self.players.iter().filter(|player| player.status == PlayerStatus::Playing).count();
+                        // With only one other player there's no one to pick between.
+                        if self.card_effects(&wild_card).contains(&Effect::SwapHands) && players_still_in > 2 {
+                            self.swap_hands_wild_color = Some(wild_color);
+                            self.ui.player_instruction = Some(PlayerInstruction::PickPlayerToSwapWith);
+                        } else {
+                            self.ui.player_instruction = None;
+                            self.wild_card_index_to_pick_color_for = None;
+                            self.ui.error = self.apply_action(self.current_player_index as usize, Action::Play {
+                                card_index: (wild_index - 1) as usize,
+                                color: Some(wild_color),
+                                swap_with: None,
+                            }).err();
+                        }
                     };
                 },
+                Command::PickPlayerToSwapWith(player_number) => {
+                    if let (Some(wild_index), Some(wild_color)) = (self.wild_card_index_to_pick_color_for, self.swap_hands_wild_color) {
+                        let result = self.apply_action(self.current_player_index as usize, Action::Play {
+                            card_index: (wild_index - 1) as usize,
+                            color: Some(wild_color),
+                            swap_with: usize::try_from(player_number - 1).ok(),
+                        });
+                        if result.is_ok() {
+                            self.ui.player_instruction = None;
+                            self.wild_card_index_to_pick_color_for = None;
+                            self.swap_hands_wild_color = None;
+                        }
+                        self.ui.error = result.err();
+                    }
+                },
                 Command::PickCardToPlay(card_index) => {
                     self.wild_card_index_to_pick_color_for = None;
-                    let current_player = &mut self.players[self.current_player_index as usize];
-                    match validate_card_from_index(card_index, &current_player.hand, self.discard.last()) {
+                    self.swap_hands_wild_color = None;
+                    let current_player = &self.players[self.current_player_index as usize];
+                    match validate_card_from_index(card_index, &current_player.hand, self.discard.last(), self.pending_draw) {
                         CardFromIndexValidationResult::Invalid(reason) => {
-                            self.wild_card_index_to_pick_color_for = None;
                             self.ui.error = Some(reason);
                         },
-                        CardFromIndexValidationResult::Valid => {
-                            let card_to_play = current_player.hand.get((card_index - 1) as usize).unwrap();
-                            // If the picked a wild card to play, then they next need to pick a color. We
-                            // wait for an upcoming Command::PickWildColor(color)
-                            if card_to_play.wild {
-                                self.ui.player_instruction = Some(PlayerInstruction::PickWildColor);
-                                self.wild_card_index_to_pick_color_for = Some(card_index);
-                            } else {
-                                let card_to_play = current_player.hand.remove((card_index - 1) as usize);
-                                self.ui.last_turn_recap = Some(TurnRecap {
-                                    player: self.current_player_index + 1,
-                                    played_cards: vec![card_to_play],
-                                    drawn_cards: 0,
-                                });
-                                self.play_card(card_to_play);
-                            }
+                        // If the picked a wild card to play, then they next need to pick a color. We
+                        // wait for an upcoming Command::PickWildColor(color)
+                        CardFromIndexValidationResult::Valid if current_player.hand[(card_index - 1) as usize].wild => {
+                            self.ui.player_instruction = Some(PlayerInstruction::PickWildColor);
+                            self.wild_card_index_to_pick_color_for = Some(card_index);
                             self.ui.error = None;
+                        },
+                        CardFromIndexValidationResult::Valid => {
+                            self.ui.error = self.apply_action(self.current_player_index as usize, Action::Play {
+                                card_index: (card_index - 1) as usize,
+                                color: None,
+                                swap_with: None,
+                            }).err();
                         }
                     }
                 },
             }
 
+            if self.history.len() > history_start {
+                let seat = player_index as usize;
+                if self.last_moves.len() <= seat {
+                    self.last_moves.resize(seat + 1, 0);
+                }
+                self.last_moves[seat] = history_start;
+            }
+
             // Let AI players go
             if !self.game_over() {
                 let mut current_player = self.players.get(self.current_player_index as usize).unwrap();
-                if current_player.ai {
-                    // The player may go many times in a row, so we add up each turn's recap into a
-                    // single larger recap that represents the everything the player did before the
-                    // next player got to go.
-                    let mut player_turn_recap: TurnRecap = TurnRecap { player: self.current_player_index + 1, played_cards: vec![], drawn_cards: 0 };
-                    while !self.game_over() && current_player.ai {
+                if current_player.is_ai() {
+                    self.explanations.clear();
+                    let mut fast_forward = self.pace == Pace::FastForward;
+                    while !self.game_over() && current_player.is_ai() {
                         let turn_recap = self.automate_current_player_turn();
-                        player_turn_recap.drawn_cards += turn_recap.drawn_cards;
-                        player_turn_recap.played_cards = player_turn_recap.played_cards.iter().cloned().chain(turn_recap.played_cards.iter().cloned()).collect();
                         current_player = self.players.get(self.current_player_index as usize).unwrap();
+                        if fast_forward {
+                            continue;
+                        }
+                        // Show each turn on its own. The human gets caught up on all of them along
+                        // with their hand, so there's no need to wait on the last one.
+                        self.ui.turn_recaps = vec![turn_recap];
+                        if !self.game_over() && current_player.is_ai() {
+                            self.ui.watching = true;
+                            self.render();
+                            fast_forward = wait_for_next_move(self.pace);
+                            self.ui.watching = false;
+                            current_player = self.players.get(self.current_player_index as usize).unwrap();
+                        }
                     }
-                    self.ui.player_instruction = Some(PlayerInstruction::PickCard);
-                    self.ui.last_turn_recap = Some(player_turn_recap);
                 }
-                self.ui.display_hand(self.current_player_index + 1, &current_player.hand);
+                let last_move = self.last_moves.get(self.current_player_index as usize).copied().unwrap_or(0);
+                self.ui.turn_recaps = self.turn_recaps_since(last_move);
+                let PlayerView { hand, backs, .. } = self.view(self.current_player_index as usize);
+                self.ui.display_hand(self.current_player_index + 1, &hand, &backs);
 
                 let players_with_uno = self.players.iter().enumerate().filter_map(|(index, player)| {
-                    if player.hand.len() == 1 {
-                        return Some((index + 1) as i32);
+                    if player.status == PlayerStatus::Playing && player.hand.len() == 1 {
+                        Some((index + 1) as i32)
                     } else {
                         None
                     }
@@ -178,218 +491,954 @@ impl Uno {
                 self.ui.uno_declarations = players_with_uno;
             }
 
+            if self.wild_card_index_to_pick_color_for.is_none() {
+                self.ui.player_instruction = if self.pending_draw > 0 {
+                    Some(PlayerInstruction::StackOrDraw(self.pending_draw))
+                } else {
+                    Some(PlayerInstruction::PickCard)
+                };
+            }
+
+            self.ui.knocked_out_players = self.knocked_out_players.iter().map(|index| index + 1).collect();
+            self.ui.finished_players = self.finished_players.iter().map(|index| index + 1).collect();
+
+            if self.rules.mode == GameMode::Flip {
+                self.ui.side = Some(self.side);
+            }
+
+            self.ui.signals = self.players.iter().enumerate().filter_map(|(index, player)| {
+                player.signal.map(|color| ((index + 1) as i32, color))
+            }).collect();
+
             if self.game_over() {
-                let winning_player = self.players.iter().enumerate().find_map(|(index, player)| {
-                    if player.hand.len() == 0 {
-                        return Some((index + 1) as i32);
-                    } else {
-                        return None;
-                    }
-                }).unwrap();
-                self.ui.winning_player = Some(winning_player);
+                let winning_player = self.winning_player_index().unwrap();
+                self.ui.winning_player = Some(winning_player + 1);
+                self.ui.winning_team = self.players[winning_player as usize].team.map(|team| team + 1);
+                self.ui.points_won = Some(self.points_won());
+                if self.rules.last_player_standing {
+                    self.ui.finishing_order = Some(self.finishing_order().iter().map(|index| index + 1).collect());
+                }
             }
         }
     }
 
-    /// Card validation should be done prior to calling this function.
-    fn play_card(&mut self, card: Card) {
-        if card.turn_effect == Some(TurnEffect::Reverse) {
-            match self.turn_order {
-                TurnOrder::Forward => self.turn_order = TurnOrder::Backward,
-                TurnOrder::Backward => self.turn_order = TurnOrder::Forward,
-            }
-        }
+    /// The seats of the computer players, in seating order.
+    pub fn ai_seats(&self) -> Vec<usize> {
+        (0..self.players.len()).filter(|seat| self.players[*seat].is_ai()).collect()
+    }
 
-        let next_player_index = {
-            let mut next_player_index = get_next_player_index(
-                self.current_player_index,
-                self.players.len() as i32,
-                self.turn_order,
-            );
+    /// Changes how the player in `seat` picks their moves, which makes them a computer player.
+    pub fn set_strategy(&mut self, seat: usize, strategy: Box<dyn Strategy>) {
+        self.players[seat].strategy = Some(strategy);
+    }
 
-            if card.turn_effect == Some(TurnEffect::Skip) {
-                next_player_index = get_next_player_index(
-                    next_player_index,
-                    self.players.len() as i32,
-                    self.turn_order,
-                );
-            }
-            next_player_index
-        };
+    /// Changes what humans get hints from. Without a hint strategy there are no hints.
+    pub fn set_hint_strategy(&mut self, strategy: Option<Box<dyn Strategy>>) {
+        self.ui.hints = strategy.is_some();
+        self.hint_strategy = strategy;
+    }
 
-        if let Some(draw_effect) = card.draw_effect {
-            play_card_draw_effect(
-                &draw_effect,
-                &mut self.players[next_player_index as usize],
-                &mut self.deck,
-                &mut self.discard
-            );
-        }
+    pub fn set_pace(&mut self, pace: Pace) {
+        self.pace = pace;
+    }
 
-        self.discard.push(card);
+    /// What the hint strategy would do in the current player's place, and why.
+    fn hint(&mut self) -> Result<(Action, Explanation), String> {
+        let mut strategy = self.hint_strategy.take().ok_or("Hints are off for this game.")?;
+        let view = self.view(self.current_player_index as usize);
+        let (action, explanation) = strategy.choose_explained_action(&view);
+        let explanation = explanation.unwrap_or_else(|| Explanation {
+            action: describe_action(&view, &action),
+            reasons: Vec::new(),
+            scores: Vec::new(),
+        });
+        self.hint_strategy = Some(strategy);
+        Ok((action, explanation))
+    }
 
-        // Set next player for next turn
-        if !self.game_over() {
-            self.current_player_index = next_player_index;
+    /// Does what `player` asked to, if `legal_actions` allows it. Otherwise returns why not.
+    pub fn apply_action(&mut self, player: usize, action: Action) -> Result<(), String> {
+        let action = self.fill_in_action(action);
+        if !self.legal_actions(player).contains(&action) {
+            return Err(self.why_action_is_illegal(player, action));
+        }
+        self.record_tendencies(player, action);
+        // Getting caught without calling Uno only counts until the player's next turn. Challenging
+        // someone else when it isn't their turn doesn't get them off the hook.
+        if player == self.current_player() && !matches!(action, Action::Challenge(_)) {
+            self.players[player].missed_uno_call = false;
+        }
+        match action {
+            Action::Play { card_index, color, swap_with } => {
+                let mut card = self.players[player].hand.remove(card_index);
+                if card.wild {
+                    card.color = color;
+                }
+                self.play_card_targeting(card, swap_with.map(|swap_with| swap_with as i32));
+            }
+            Action::Draw if self.pending_draw > 0 => self.take_draw_penalty(),
+            Action::Draw => {
+                self.players[player].drew_this_turn = true;
+                self.current_player_draws(1, false);
+            }
+            Action::Pass => {
+                self.end_turn_for(player);
+                self.current_player_index = get_next_active_player_index(self.current_player_index, &self.players, self.turn_order);
+            }
+            Action::CallUno => self.players[player].called_uno = true,
+            Action::Challenge(target) => {
+                self.history.push(Event::Challenged { player, target });
+                // Like penalties, draws are limited to what's left to draw.
+                let count = 2.min(self.deck.len() + self.discard.len());
+                let target_player = &mut self.players[target];
+                target_player.missed_uno_call = false;
+                draw_cards(&mut target_player.hand, count as i32, &mut self.deck, &mut self.discard, &mut self.rng);
+                self.history.push(Event::Drew { player: target, count, forced: true });
+                self.apply_mercy_rule(target as i32);
+            }
+            Action::Signal(color) => {
+                let player = &mut self.players[player];
+                player.signal = Some(color);
+                player.signaled_this_turn = true;
+            }
         }
+        Ok(())
     }
 
-    fn automate_current_player_turn(&mut self) -> TurnRecap {
-        let player = &mut self.players[self.current_player_index as usize];
-        let mut num_drawn_cards = 0;
-        let mut card_index_to_play: Option<usize> = None;
-        while card_index_to_play.is_none() {
-            let last_played_card: Option<&Card> = self.discard.last();
-            if let Some((card_index, _)) = player.hand.iter().enumerate().find(|(_, card)| can_play_card(last_played_card, card)) {
-                card_index_to_play = Some(card_index);
-            } else {
-                num_drawn_cards += 1;
-                draw_cards(
-                    &mut player.hand,
-                    1,
-                    &mut self.deck,
-                    &mut self.discard,
-                );
+    /// Fills in the parts of an action that can be left out. Swap hands cards played without
+    /// saying who to swap with swap with whoever has the fewest cards, and parts that don't matter
+    /// for the card are dropped.
+    fn fill_in_action(&self, action: Action) -> Action {
+        let Action::Play { card_index, color, swap_with } = action else {
+            return action;
+        };
+        let Some(card) = self.players[self.current_player_index as usize].hand.get(card_index) else {
+            return action;
+        };
+        let swaps_hands = self.card_effects(card).contains(&Effect::SwapHands);
+        Action::Play {
+            card_index,
+            color: color.filter(|_| card.wild),
+            swap_with: match swap_with {
+                Some(swap_with) if swaps_hands => Some(swap_with),
+                None if swaps_hands => self.player_with_fewest_cards_besides(self.current_player_index).map(|player| player as usize),
+                _ => None,
+            },
+        }
+    }
+
+    fn why_action_is_illegal(&self, player: usize, action: Action) -> String {
+        if self.game_over() {
+            return "The game is over.".to_string();
+        }
+        if player != self.current_player_index as usize && !matches!(action, Action::Challenge(_)) {
+            return "It's not your turn.".to_string();
+        }
+        match action {
+            Action::Play { card_index, .. } => {
+                let hand = &self.players[player].hand;
+                match validate_card_from_index(card_index as i32 + 1, hand, self.discard.last(), self.pending_draw) {
+                    CardFromIndexValidationResult::Invalid(reason) => reason,
+                    CardFromIndexValidationResult::Valid if hand[card_index].wild => "Pick a color from the side that's showing for your wild.".to_string(),
+                    CardFromIndexValidationResult::Valid => "You can't swap hands with that player, pick another.".to_string(),
+                }
             }
+            Action::Draw => "There are no cards left to draw.".to_string(),
+            Action::Pass => "You can only pass after drawing a card.".to_string(),
+            Action::CallUno => "You can only call Uno when you're down to two cards.".to_string(),
+            Action::Challenge(_) => "Only players who didn't call Uno before getting down to one card can be challenged.".to_string(),
+            Action::Signal(_) if self.players[player].signaled_this_turn => "You've already signaled this turn.".to_string(),
+            Action::Signal(_) => "You can't signal that.".to_string(),
         }
-        let mut card_to_play = player.hand.remove(card_index_to_play.unwrap());
-        if card_to_play.wild {
-            let mut color_counters: Vec<(Color, i32)> = vec![
-                {(Color::Red, 0)},
-                {(Color::Blue, 0)},
-                {(Color::Yellow, 0)},
-                {(Color::Green, 0)},
-            ];
-            for counter in &mut color_counters {
-                counter.1 = player.hand.iter().filter(|card| card.color == Some(counter.0)).count() as i32;
+    }
+
+    /// Adds what `player` is about to do to how they've played this game. Only the first move of
+    /// a turn says anything about their hand, since later ones are after drawing.
+    fn record_tendencies(&mut self, player: usize, action: Action) {
+        let hand = &self.players[player].hand;
+        let profile = &mut self.profiles[player];
+        let first_move = self.pending_draw == 0 && !self.players[player].drew_this_turn;
+        if let (Action::Play { .. } | Action::Draw, true) = (action, first_move) {
+            profile.holding_wilds.record(hand.iter().any(|card| card.wild));
+            if let Some(color) = self.discard.last().and_then(|card| card.color) {
+                profile.draws_on_mut(color).record(action == Action::Draw);
             }
-            color_counters.sort_by_key(|(_, count)| *count);
-            let color_with_most_cards = color_counters.first().unwrap().0;
-            card_to_play.color = Some(color_with_most_cards);
         }
+        if matches!(action, Action::Play { .. }) && hand.len() == 2 {
+            profile.uno_calls.record(self.players[player].called_uno);
+        }
+    }
 
-        let turn_recap = TurnRecap {
-            player: self.current_player_index + 1,
-            played_cards: vec![card_to_play],
-            drawn_cards: num_drawn_cards,
-        };
+    /// How each player has played this game so far, by seat.
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
 
-        self.play_card(card_to_play);
+    /// Lets computer players know how each seat played in earlier games.
+    pub fn set_past_profiles(&mut self, profiles: Vec<Profile>) {
+        self.past_profiles = profiles;
+    }
 
-        return turn_recap;
+    /// Every action `player` could take right now. Players can only challenge others when it
+    /// isn't their turn.
+    pub fn legal_actions(&self, player: usize) -> Vec<Action> {
+        if self.game_over() {
+            return Vec::new();
+        }
+        self.view(player).legal_actions()
     }
 
-    pub fn render(&self) {
-        self.ui.render();
+    /// The game as the player in `seat` sees it.
+    pub fn view(&self, seat: usize) -> PlayerView<'_> {
+        let reversed_turn_order = match self.turn_order {
+            TurnOrder::Forward => TurnOrder::Backward,
+            TurnOrder::Backward => TurnOrder::Forward,
+        };
+        let player = &self.players[seat];
+        PlayerView {
+            seat,
+            current_player: self.current_player_index as usize,
+            hand: player.hand.iter().map(|card| Card { flip_side: None, ..*card }).collect(),
+            backs: self.players.iter().enumerate()
+                .map(|(other_seat, other_player)| if other_seat == seat {
+                    Vec::new()
+                } else {
+                    other_player.hand.iter().filter_map(|card| card.flip_side).collect()
+                })
+                .collect(),
+            top_card: self.discard.last().map(|card| Card { flip_side: None, ..*card }),
+            hand_sizes: self.players.iter().map(|player| player.hand.len()).collect(),
+            statuses: self.players.iter().map(|player| player.status).collect(),
+            teams: self.players.iter().map(|player| player.team).collect(),
+            signals: self.players.iter().map(|player| player.signal).collect(),
+            signaled_this_turn: player.signaled_this_turn,
+            drew_this_turn: player.drew_this_turn,
+            called_uno: player.called_uno,
+            missed_uno_calls: self.players.iter().map(|player| player.missed_uno_call).collect(),
+            turn_order: self.turn_order,
+            next_player: get_next_active_player_index(seat as i32, &self.players, self.turn_order) as usize,
+            previous_player: get_next_active_player_index(seat as i32, &self.players, reversed_turn_order) as usize,
+            pending_draw: self.pending_draw,
+            deck_size: self.deck.len(),
+            discard_size: self.discard.len(),
+            side: self.side,
+            rules: self.rules,
+            history: &self.history,
+            profiles: &self.past_profiles,
+        }
     }
 
-    pub fn game_over(&self) -> bool {
-        self.players.iter().any(|player| player.hand.len() == 0)
+    /// A copy of the game as it stands, for trying out moves on. Nobody in it has a strategy, and
+    /// the history, the screen, and anything a human was partway through picking are left behind.
+    pub fn copy_position(&self) -> Uno {
+        Uno {
+            players: self.players.iter()
+                .map(|player| Player {
+                    hand: player.hand.clone(),
+                    strategy: None,
+                    status: player.status,
+                    team: player.team,
+                    signal: player.signal,
+                    signaled_this_turn: player.signaled_this_turn,
+                    drew_this_turn: player.drew_this_turn,
+                    called_uno: player.called_uno,
+                    missed_uno_call: player.missed_uno_call,
+                })
+                .collect(),
+            current_player_index: self.current_player_index,
+            deck: self.deck.clone(),
+            discard: self.discard.clone(),
+            wild_card_index_to_pick_color_for: None,
+            swap_hands_wild_color: None,
+            turn_order: self.turn_order,
+            rules: self.rules,
+            side: self.side,
+            pending_draw: self.pending_draw,
+            finished_players: self.finished_players.clone(),
+            knocked_out_players: self.knocked_out_players.clone(),
+            history: Vec::new(),
+            profiles: vec![Profile::default(); self.players.len()],
+            past_profiles: self.past_profiles.clone(),
+            last_moves: Vec::new(),
+            explanations: Vec::new(),
+            hint_strategy: None,
+            pace: Pace::default(),
+            rng: self.rng.clone(),
+            ui: UI::default(),
+        }
     }
-}
 
-fn play_card_draw_effect(
-    draw_effect: &DrawEffect,
-    next_player: &mut Player,
-    deck: &mut Vec<Card>,
-    discard: &mut Vec<Card>
-) {
-    let DrawEffect::Draw(num_cards_to_draw) = draw_effect;
-    draw_cards(
-        &mut next_player.hand,
-        *num_cards_to_draw,
-        deck,
-        discard,
-    );
-}
+    /// The cards left to draw, the next one last. Only strategies searching through games they've
+    /// dealt out themselves should look at this.
+    pub fn deck(&self) -> &[Card] {
+        &self.deck
+    }
 
-enum CardFromIndexValidationResult {
-    Valid,
-    Invalid(String),
-}
+    /// Makes the cards at `deck_indexes` the next ones drawn, in that order, so searches can try out
+    /// each card a draw could turn up.
+    pub fn deal_next(&mut self, deck_indexes: &[usize]) {
+        let cards: Vec<Card> = deck_indexes.iter().map(|deck_index| self.deck[*deck_index]).collect();
+        let mut deck_index = 0;
+        self.deck.retain(|_| {
+            deck_index += 1;
+            !deck_indexes.contains(&(deck_index - 1))
+        });
+        // Cards get drawn from the end.
+        self.deck.extend(cards.into_iter().rev());
+    }
 
-fn validate_card_from_index(card_index: i32, player_hand: &Vec<Card>, last_played_card: Option<&Card>) -> CardFromIndexValidationResult {
-    let card_to_play = player_hand.get((card_index - 1) as usize);
-    if card_to_play.is_none() {
-        return CardFromIndexValidationResult::Invalid("You do not have that card, please pick another.".to_string());
+    /// Puts the discard pile into the deck the way running out of cards to draw does, but without
+    /// shuffling it, so searches can try out each card a draw could turn up.
+    pub fn collect_discard(&mut self) {
+        return_discard_to_deck(&mut self.deck, &mut self.discard);
     }
 
-    let card_to_play = card_to_play.unwrap();
+    /// A number standing for the position, for searches to remember positions they've seen by.
+    /// Positions that play out the same way get the same number, whatever order the cards in each
+    /// hand and pile are in. Who's called Uno and signals aren't counted. Different positions can
+    /// get the same number, but it's rare enough that searches can ignore it.
+    pub fn position_hash(&self) -> u64 {
+        // Cards are hashed one by one and sorted, so the order they're in doesn't matter.
+        let card_hashes = |cards: &[Card]| -> Vec<u64> {
+            let mut card_hashes: Vec<u64> = cards.iter()
+                .map(|card| {
+                    let mut hasher = DefaultHasher::new();
+                    (card.face(), card.flip_side).hash(&mut hasher);
+                    hasher.finish()
+                })
+                .collect();
+            card_hashes.sort_unstable();
+            card_hashes
+        };
+        let mut hasher = DefaultHasher::new();
+        (self.current_player_index, self.turn_order, self.side, self.pending_draw).hash(&mut hasher);
+        self.discard.last().map(|card| (card.face(), card.flip_side)).hash(&mut hasher);
+        card_hashes(&self.discard).hash(&mut hasher);
+        for player in &self.players {
+            (player.status, player.drew_this_turn, card_hashes(&player.hand)).hash(&mut hasher);
+        }
+        card_hashes(&self.deck).hash(&mut hasher);
+        (&self.finished_players, &self.knocked_out_players).hash(&mut hasher);
+        hasher.finish()
+    }
 
-    if !can_play_card(last_played_card, &card_to_play) {
-        return CardFromIndexValidationResult::Invalid("Can't play that card :( , pick another.".to_string());
+    /// Cards the current player has to draw unless they stack another draw card on top.
+    pub fn pending_draw(&self) -> i32 {
+        self.pending_draw
     }
-    return CardFromIndexValidationResult::Valid
-}
 
-fn get_next_player_index(
-    current_player_index: i32,
-    num_players: i32,
-    turn_order: TurnOrder,
-) -> i32 {
-    let change_direction = match turn_order {
-        TurnOrder::Forward => 1,
-        TurnOrder::Backward => -1,
-    };
-    let mut next_player_index = current_player_index + change_direction;
-    if next_player_index == num_players {
-        next_player_index = 0;
-    } else if next_player_index < 0 {
-        next_player_index = num_players - 1;
+    /// Sets up a game at the position `view` shows, with the cards the viewing player can't see
+    /// filled in. `hands` has a hand for every seat, including the viewing player's, whose cards
+    /// need their backs filled in for Uno Flip. `discard` is the discard pile under the top card.
+    /// It's the viewing player's turn. Strategies use this to play out how a game might go.
+    pub fn from_position(view: &PlayerView, hands: Vec<Vec<Card>>, deck: Vec<Card>, mut discard: Vec<Card>) -> Uno {
+        let players: Vec<Player> = hands.into_iter().enumerate()
+            .map(|(seat, hand)| Player {
+                hand,
+                strategy: None,
+                status: view.statuses[seat],
+                team: view.teams[seat],
+                signal: view.signals[seat],
+                signaled_this_turn: seat == view.seat && view.signaled_this_turn,
+                drew_this_turn: seat == view.seat && view.drew_this_turn,
+                called_uno: seat == view.seat && view.called_uno,
+                missed_uno_call: view.missed_uno_calls[seat],
+            })
+            .collect();
+        let seats_with_status = |status: PlayerStatus| -> Vec<i32> {
+            (0..players.len() as i32).filter(|seat| players[*seat as usize].status == status).collect()
+        };
+        Uno {
+            current_player_index: view.seat as i32,
+            finished_players: seats_with_status(PlayerStatus::Finished),
+            knocked_out_players: seats_with_status(PlayerStatus::Eliminated),
+            players,
+            deck,
+            discard: {
+                discard.extend(view.top_card);
+                discard
+            },
+            wild_card_index_to_pick_color_for: None,
+            swap_hands_wild_color: None,
+            turn_order: view.turn_order,
+            rules: view.rules,
+            side: view.side,
+            pending_draw: view.pending_draw,
+            history: Vec::new(),
+            profiles: vec![Profile::default(); view.hand_sizes.len()],
+            past_profiles: view.profiles.to_vec(),
+            last_moves: Vec::new(),
+            explanations: Vec::new(),
+            hint_strategy: None,
+            pace: Pace::default(),
+            rng: unseeded_rng(),
+            ui: UI::default(),
+        }
     }
-    next_player_index
-}
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum TurnOrder {
-    Forward,
-    Backward,
-}
+    /// Whose turn it is.
+    pub fn current_player(&self) -> usize {
+        self.current_player_index as usize
+    }
 
-impl Default for Uno {
-    fn default() -> Self {
-        Uno::new(1, 1)
+    pub fn hand(&self, seat: usize) -> &[Card] {
+        &self.players[seat].hand
     }
-}
 
-pub fn can_play_card(prev_card: Option<&Card>, next_card: &Card) -> bool {
-    if prev_card.is_none() {
-        return true;
+    /// Everything a player did since `history_start`.
+    fn turn_recap_since(&self, player_index: i32, history_start: usize) -> TurnRecap {
+        self.turn_recap(player_index as usize, &self.history[history_start..])
     }
-    let prev_card = prev_card.unwrap();
-    if next_card.wild {
-        return true;
+
+    /// Everything that happened since `history_start`, one recap per turn. Draws and skips forced
+    /// on a player are part of the turn of whoever forced them.
+    fn turn_recaps_since(&self, history_start: usize) -> Vec<TurnRecap> {
+        // Who each turn belonged to, and where in the history it started.
+        let mut turns: Vec<(usize, usize)> = Vec::new();
+        for (index, event) in self.history.iter().enumerate().skip(history_start) {
+            let player = match *event {
+                Event::Played { player, .. }
+                | Event::Discarded { player, .. }
+                | Event::Challenged { player, .. }
+                | Event::Drew { player, forced: false, .. } => player,
+                Event::Drew { .. } | Event::Skipped { .. } if !turns.is_empty() => continue,
+                Event::Drew { player, .. } | Event::Skipped { player } => player,
+            };
+            if turns.last().map(|(turn_player, _)| *turn_player) != Some(player) {
+                turns.push((player, index));
+            }
+        }
+        turns.iter().enumerate()
+            .map(|(turn, (player, start))| {
+                let end = turns.get(turn + 1).map_or(self.history.len(), |(_, next_start)| *next_start);
+                self.turn_recap(*player, &self.history[*start..end])
+            })
+            .collect()
     }
-    // If same color
-    if let (Some(next_card_color), Some(prev_card_color)) = (next_card.color, prev_card.color) {
-        if next_card_color == prev_card_color {
-            return true;
+
+    /// What `events` show a player doing, and what it did to everyone else.
+    fn turn_recap(&self, player_index: usize, events: &[Event]) -> TurnRecap {
+        let mut turn_recap = TurnRecap { player: player_index as i32 + 1, ..TurnRecap::default() };
+        for event in events {
+            match *event {
+                Event::Played { player, card } if player == player_index => {
+                    turn_recap.played_cards.push(card);
+                    turn_recap.reversed |= self.card_effects(&card).contains(&Effect::Reverse);
+                }
+                Event::Drew { player, count, .. } if player == player_index => turn_recap.drawn_cards += count as i32,
+                Event::Drew { player, count, .. } => turn_recap.forced_draws.push((player as i32 + 1, count as i32)),
+                Event::Challenged { player, target } if player == player_index => turn_recap.challenged_players.push(target as i32 + 1),
+                Event::Skipped { player } => turn_recap.skipped_players.push(player as i32 + 1),
+                _ => {}
+            }
         }
+        turn_recap
     }
-    // If same number
-    if let (Some(next_card_number), Some(prev_card_number)) = (next_card.number, prev_card.number) {
-        if next_card_number == prev_card_number {
-            return true;
+
+    /// Card validation should be done prior to calling this function.
+    #[cfg(test)]
+    fn play_card(&mut self, card: Card) {
+        self.play_card_targeting(card, None);
+    }
+
+    /// Plays a card and resolves each of its effects in order. `swap_target` is who the player
+    /// swaps hands with if the card swaps hands. Without one they swap with whoever has the fewest
+    /// cards.
+    fn play_card_targeting(&mut self, mut card: Card, swap_target: Option<i32>) {
+        self.history.push(Event::Played { player: self.current_player_index as usize, card });
+        let mut turn_change = TurnChange::default();
+        for effect in self.card_effects(&card) {
+            self.resolve_effect(effect, &mut card, swap_target, &mut turn_change);
+        }
+
+        self.discard.push(card);
+        let player = &mut self.players[self.current_player_index as usize];
+        player.missed_uno_call = player.hand.len() == 1 && !player.called_uno;
+        self.end_turn_for(self.current_player_index as usize);
+        self.finish_players_who_went_out();
+
+        // Set next player for next turn. A player who just went out can't go again.
+        let current_player_still_in = self.players[self.current_player_index as usize].status == PlayerStatus::Playing;
+        if !self.game_over() && (!turn_change.play_again || !current_player_still_in) {
+            for skip in 0..(1 + turn_change.players_skipped) {
+                self.current_player_index = get_next_active_player_index(
+                    self.current_player_index,
+                    &self.players,
+                    self.turn_order,
+                );
+                if skip < turn_change.players_skipped {
+                    self.history.push(Event::Skipped { player: self.current_player_index as usize });
+                }
+            }
+        } else if !self.game_over() {
+            let player = self.current_player_index as usize;
+            let skipped: Vec<usize> = (0..self.players.len())
+                .filter(|other| *other != player && self.players[*other].status == PlayerStatus::Playing)
+                .collect();
+            self.history.extend(skipped.into_iter().map(|player| Event::Skipped { player }));
         }
     }
-    // If same turn effect
-    if let (Some(next_card_turn_effect), Some(prev_card_turn_effect)) =
-        (next_card.turn_effect, prev_card.turn_effect)
-    {
-        if next_card_turn_effect == prev_card_turn_effect {
-            return true;
+
+    /// In last player standing games, players who ran out of cards leave the game. The current
+    /// player is checked first since swapping hands can empty someone else's hand too.
+    fn finish_players_who_went_out(&mut self) {
+        if !self.rules.last_player_standing {
+            return;
+        }
+        let player_count = self.players.len() as i32;
+        for offset in 0..player_count {
+            let player_index = (self.current_player_index + offset) % player_count;
+            let player = &mut self.players[player_index as usize];
+            if player.status == PlayerStatus::Playing && player.hand.is_empty() {
+                player.status = PlayerStatus::Finished;
+                self.finished_players.push(player_index);
+            }
         }
     }
-    // If both cause drawing cards
-    if next_card.draw_effect.is_some() && prev_card.draw_effect.is_some() {
-        return true;
+
+    fn card_effects(&self, card: &Card) -> Vec<Effect> {
+        self.rules.card_effects(card)
     }
-    return false;
-}
 
-pub fn create_deck() -> Vec<Card> {
-    let mut deck: Vec<Card> = Vec::new();
-    // There are 108 cards in a standard Uno deck.
+    fn resolve_effect(&mut self, effect: Effect, card: &mut Card, swap_target: Option<i32>, turn_change: &mut TurnChange) {
+        let next_player_index = get_next_active_player_index(
+            self.current_player_index,
+            &self.players,
+            self.turn_order,
+        );
+        match effect {
+            Effect::Reverse => {
+                match self.turn_order {
+                    TurnOrder::Forward => self.turn_order = TurnOrder::Backward,
+                    TurnOrder::Backward => self.turn_order = TurnOrder::Forward,
+                }
+            }
+            Effect::Skip => turn_change.players_skipped += 1,
+            // Everyone else is skipped, so the same player goes again.
+            Effect::SkipEveryone => turn_change.play_again = true,
+            // The next player can pass the penalty on by stacking, so they don't draw yet.
+            Effect::Draw(num_cards_to_draw) if self.rules.stacking() => {
+                self.pending_draw += num_cards_to_draw;
+            }
+            Effect::Draw(num_cards_to_draw) => {
+                // Like penalties, draws are limited to what's left to draw.
+                let num_cards_to_draw = num_cards_to_draw.min((self.deck.len() + self.discard.len()) as i32);
+                self.history.push(Event::Drew { player: next_player_index as usize, count: num_cards_to_draw as usize, forced: true });
+                play_card_draw_effect(
+                    &DrawEffect::Draw(num_cards_to_draw),
+                    card.color,
+                    &mut self.players[next_player_index as usize],
+                    &mut self.deck,
+                    &mut self.discard,
+                    &mut self.rng,
+                );
+            }
+            Effect::DrawColor => {
+                let next_player = &mut self.players[next_player_index as usize];
+                let hand_size_before = next_player.hand.len();
+                play_card_draw_effect(
+                    &DrawEffect::DrawColor,
+                    card.color,
+                    next_player,
+                    &mut self.deck,
+                    &mut self.discard,
+                    &mut self.rng,
+                );
+                let count = self.players[next_player_index as usize].hand.len() - hand_size_before;
+                self.history.push(Event::Drew { player: next_player_index as usize, count, forced: true });
+            }
+            Effect::DiscardAll => {
+                let hand = &mut self.players[self.current_player_index as usize].hand;
+                let same_colored_cards: Vec<Card> = hand.iter().filter(|hand_card| hand_card.color == card.color).copied().collect();
+                hand.retain(|hand_card| hand_card.color != card.color);
+                let player = self.current_player_index as usize;
+                self.history.extend(same_colored_cards.iter().map(|card| Event::Discarded { player, card: *card }));
+                self.discard.extend(same_colored_cards);
+            }
+            Effect::SwapHands => {
+                let target_index = swap_target.or_else(|| self.player_with_fewest_cards_besides(self.current_player_index));
+                if let Some(target_index) = target_index {
+                    let current_hand = std::mem::take(&mut self.players[self.current_player_index as usize].hand);
+                    let target_hand = std::mem::replace(&mut self.players[target_index as usize].hand, current_hand);
+                    self.players[self.current_player_index as usize].hand = target_hand;
+                }
+            }
+            Effect::ShuffleHands => {
+                let mut all_cards: Vec<Card> = self.players.iter_mut()
+                    .flat_map(|player| std::mem::take(&mut player.hand))
+                    .collect();
+                all_cards.shuffle(&mut self.rng);
+                // Deal the cards back out one at a time, starting with the next player.
+                let mut player_index = next_player_index;
+                for card in all_cards {
+                    self.players[player_index as usize].hand.push(card);
+                    player_index = get_next_active_player_index(player_index, &self.players, self.turn_order);
+                }
+            }
+            Effect::Flip => {
+                self.flip();
+                card.flip();
+            }
+        }
+    }
+
+    fn player_with_fewest_cards_besides(&self, player_index: i32) -> Option<i32> {
+        self.players.iter().enumerate()
+            .filter(|(index, player)| *index as i32 != player_index && player.status == PlayerStatus::Playing)
+            .min_by_key(|(_, player)| player.hand.len())
+            .map(|(index, _)| index as i32)
+    }
+
+    /// Resets what a player can only do once a turn.
+    fn end_turn_for(&mut self, player_index: usize) {
+        let player = &mut self.players[player_index];
+        player.signaled_this_turn = false;
+        player.drew_this_turn = false;
+        player.called_uno = false;
+    }
+
+    /// Turns every card in the game over. The card being played isn't in the game yet, so it has to
+    /// be flipped separately.
+    fn flip(&mut self) {
+        self.side = match self.side {
+            Side::Light => Side::Dark,
+            Side::Dark => Side::Light,
+        };
+        let hands = self.players.iter_mut().flat_map(|player| player.hand.iter_mut());
+        for card in hands.chain(self.deck.iter_mut()).chain(self.discard.iter_mut()) {
+            card.flip();
+        }
+    }
+
+    /// The current player draws cards. In No Mercy games drawing too many knocks the player out,
+    /// which makes it the next player's turn. `forced` is whether another player's card made them
+    /// draw.
+    fn current_player_draws(&mut self, num_cards: i32, forced: bool) {
+        let current_player = &mut self.players[self.current_player_index as usize];
+        draw_cards(
+            &mut current_player.hand,
+            num_cards,
+            &mut self.deck,
+            &mut self.discard,
+            &mut self.rng,
+        );
+        self.history.push(Event::Drew { player: self.current_player_index as usize, count: num_cards as usize, forced });
+        if self.apply_mercy_rule(self.current_player_index) {
+            self.current_player_index = get_next_active_player_index(
+                self.current_player_index,
+                &self.players,
+                self.turn_order,
+            );
+        }
+    }
+
+    /// The current player couldn't or didn't want to stack a draw card, so they draw every card
+    /// that's been stacked up and lose their turn.
+    fn take_draw_penalty(&mut self) {
+        let player_index = self.current_player_index;
+        // Hands can hold most of the cards in a long No Mercy game, so the penalty is limited to
+        // what's left to draw.
+        let num_cards = self.pending_draw.min((self.deck.len() + self.discard.len()) as i32);
+        self.pending_draw = 0;
+        self.end_turn_for(player_index as usize);
+        self.current_player_draws(num_cards, true);
+        if self.current_player_index == player_index {
+            self.current_player_index = get_next_active_player_index(
+                player_index,
+                &self.players,
+                self.turn_order,
+            );
+        }
+    }
+
+    /// Knocks the player out if they're holding too many cards. Their cards go to the bottom of
+    /// the deck. Returns whether the player was knocked out.
+    fn apply_mercy_rule(&mut self, player_index: i32) -> bool {
+        let player = &mut self.players[player_index as usize];
+        match self.rules.mercy_limit() {
+            Some(mercy_limit) if player.hand.len() >= mercy_limit => {
+                player.status = PlayerStatus::Eliminated;
+                let hand = std::mem::take(&mut player.hand);
+                self.deck.splice(0..0, hand);
+                self.knocked_out_players.push(player_index);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Lets the current player's strategy play out their turn. Players without a strategy play
+    /// like a basic computer player.
+    pub fn automate_current_player_turn(&mut self) -> TurnRecap {
+        let player_index = self.current_player_index;
+        let history_start = self.history.len();
+        let mut strategy = self.players[player_index as usize].strategy.take();
+        let mut basic_strategy = BasicStrategy;
+        // Only humans can ask why a move was made, so there's no need to work it out otherwise.
+        let explain = self.players.iter().enumerate()
+            .any(|(seat, player)| seat != player_index as usize && !player.is_ai());
+        while self.current_player_index == player_index && !self.game_over() {
+            let view = self.view(player_index as usize);
+            let strategy: &mut dyn Strategy = match &mut strategy {
+                Some(strategy) => strategy.as_mut(),
+                None => &mut basic_strategy,
+            };
+            let (action, explanation) = if explain {
+                strategy.choose_explained_action(&view)
+            } else {
+                (strategy.choose_action(&view), None)
+            };
+            self.explanations.extend(explanation.map(|explanation| (player_index as usize, explanation)));
+            // A strategy that asks for something the rules don't allow gets a basic move instead,
+            // so the game can't get stuck on it.
+            if self.apply_action(player_index as usize, action).is_err() {
+                let fallback_action = basic_strategy.choose_action(&self.view(player_index as usize));
+                self.apply_action(player_index as usize, fallback_action).expect("basic strategy only picks legal actions");
+            }
+        }
+        self.players[player_index as usize].strategy = strategy;
+        self.turn_recap_since(player_index, history_start)
+    }
+
+    pub fn render(&self) {
+        self.ui.render();
+    }
+
+    /// The game ends when a player gets rid of all their cards, or when only one player (or one
+    /// team) is left.
+    pub fn game_over(&self) -> bool {
+        let players_still_in: Vec<&Player> = self.players.iter().filter(|player| player.status == PlayerStatus::Playing).collect();
+        if players_still_in.iter().any(|player| player.hand.is_empty()) {
+            return true;
+        }
+        match self.rules.teams {
+            Some(_) => players_still_in.windows(2).all(|pair| pair[0].team == pair[1].team),
+            None => players_still_in.len() < 2,
+        }
+    }
+
+    /// The player who got rid of all their cards first, or the last player left once everyone
+    /// else has been knocked out. In team games their whole team wins with them.
+    pub fn winning_player_index(&self) -> Option<i32> {
+        if !self.game_over() {
+            return None;
+        }
+        self.finishing_order().first().copied()
+    }
+
+    /// Every player from first place to last. Players who went out come first in the order they
+    /// went out, then players still holding cards from fewest to most, then players who were
+    /// knocked out with the last one knocked out placing highest.
+    pub fn finishing_order(&self) -> Vec<i32> {
+        let mut players_still_in: Vec<i32> = (0..self.players.len() as i32)
+            .filter(|index| self.players[*index as usize].status == PlayerStatus::Playing)
+            .collect();
+        players_still_in.sort_by_key(|index| self.players[*index as usize].hand.len());
+        self.finished_players.iter().copied()
+            .chain(players_still_in)
+            .chain(self.knocked_out_players.iter().rev().copied())
+            .collect()
+    }
+
+    /// Whether two players are on the same team. Players are never teammates with themselves.
+    fn are_teammates(&self, player_index: i32, other_player_index: i32) -> bool {
+        let team = self.players[player_index as usize].team;
+        player_index != other_player_index && team.is_some() && team == self.players[other_player_index as usize].team
+    }
+
+    /// The winner scores the points for every card their opponents are still holding. Teammates
+    /// share the score, so their own cards don't count.
+    fn points_won(&self) -> i32 {
+        match self.winning_player_index() {
+            Some(winning_player_index) => self.players.iter().enumerate()
+                .filter(|(index, _)| {
+                    *index as i32 != winning_player_index && !self.are_teammates(winning_player_index, *index as i32)
+                })
+                .flat_map(|(_, player)| player.hand.iter())
+                .map(Card::points)
+                .sum(),
+            None => 0,
+        }
+    }
+}
+
+/// `color` is the color of the card that was played, which matters for draw color cards.
+fn play_card_draw_effect(
+    draw_effect: &DrawEffect,
+    color: Option<Color>,
+    next_player: &mut Player,
+    deck: &mut Vec<Card>,
+    discard: &mut Vec<Card>,
+    rng: &mut impl Rng,
+) {
+    match draw_effect {
+        DrawEffect::Draw(num_cards_to_draw) => {
+            draw_cards(
+                &mut next_player.hand,
+                *num_cards_to_draw,
+                deck,
+                discard,
+                rng,
+            );
+        }
+        DrawEffect::DrawColor => {
+            // Keep drawing until the wild's color turns up, or until there's nothing left to draw.
+            while !deck.is_empty() || !discard.is_empty() {
+                draw_cards(&mut next_player.hand, 1, deck, discard, rng);
+                if next_player.hand.last().unwrap().color == color {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// How a card changes who goes next.
+#[derive(Default)]
+struct TurnChange {
+    players_skipped: i32,
+    play_again: bool,
+}
+
+fn parse_color(color_text: &str) -> Option<Color> {
+    match color_text {
+        "r" | "red" => Some(Color::Red),
+        "b" | "blue" => Some(Color::Blue),
+        "g" | "green" => Some(Color::Green),
+        "y" | "yellow" => Some(Color::Yellow),
+        "pink" => Some(Color::Pink),
+        "teal" => Some(Color::Teal),
+        "orange" => Some(Color::Orange),
+        "purple" => Some(Color::Purple),
+        _ => None,
+    }
+}
+
+enum CardFromIndexValidationResult {
+    Valid,
+    Invalid(String),
+}
+
+fn validate_card_from_index(card_index: i32, player_hand: &[Card], last_played_card: Option<&Card>, pending_draw: i32) -> CardFromIndexValidationResult {
+    let card_to_play = player_hand.get((card_index - 1) as usize);
+    if card_to_play.is_none() {
+        return CardFromIndexValidationResult::Invalid("You do not have that card, please pick another.".to_string());
+    }
+
+    let card_to_play = card_to_play.unwrap();
+
+    if pending_draw > 0 && !can_stack_card(last_played_card, card_to_play) {
+        return CardFromIndexValidationResult::Invalid(format!("You have to stack a draw card that draws at least as many cards, or draw {} cards.", pending_draw));
+    }
+
+    if !can_play_card(last_played_card, card_to_play) {
+        return CardFromIndexValidationResult::Invalid("Can't play that card :( , pick another.".to_string());
+    }
+    CardFromIndexValidationResult::Valid
+}
+
+fn get_next_player_index(
+    current_player_index: i32,
+    num_players: i32,
+    turn_order: TurnOrder,
+) -> i32 {
+    let change_direction = match turn_order {
+        TurnOrder::Forward => 1,
+        TurnOrder::Backward => -1,
+    };
+    let mut next_player_index = current_player_index + change_direction;
+    if next_player_index == num_players {
+        next_player_index = 0;
+    } else if next_player_index < 0 {
+        next_player_index = num_players - 1;
+    }
+    next_player_index
+}
+
+/// Like `get_next_player_index` but skips over players who are out of the game.
+fn get_next_active_player_index(
+    current_player_index: i32,
+    players: &[Player],
+    turn_order: TurnOrder,
+) -> i32 {
+    let mut next_player_index = get_next_player_index(current_player_index, players.len() as i32, turn_order);
+    while players[next_player_index as usize].status != PlayerStatus::Playing && next_player_index != current_player_index {
+        next_player_index = get_next_player_index(next_player_index, players.len() as i32, turn_order);
+    }
+    next_player_index
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnOrder {
+    Forward,
+    Backward,
+}
+
+/// An rng for games that don't need to play out the same way twice.
+fn unseeded_rng() -> StdRng {
+    StdRng::from_rng(thread_rng()).expect("the thread rng can always seed another rng")
+}
+
+impl Default for Uno {
+    fn default() -> Self {
+        Uno::new(1, 1)
+    }
+}
+
+pub fn can_play_card(prev_card: Option<&Card>, next_card: &Card) -> bool {
+    if prev_card.is_none() {
+        return true;
+    }
+    let prev_card = prev_card.unwrap();
+    if next_card.wild {
+        return true;
+    }
+    // A wild that was flipped face up never had a color picked, so anything goes.
+    if prev_card.wild && prev_card.color.is_none() {
+        return true;
+    }
+    // If same color
+    if let (Some(next_card_color), Some(prev_card_color)) = (next_card.color, prev_card.color) {
+        if next_card_color == prev_card_color {
+            return true;
+        }
+    }
+    // If same number
+    if let (Some(next_card_number), Some(prev_card_number)) = (next_card.number, prev_card.number) {
+        if next_card_number == prev_card_number {
+            return true;
+        }
+    }
+    // If same turn effect
+    if let (Some(next_card_turn_effect), Some(prev_card_turn_effect)) =
+        (next_card.turn_effect, prev_card.turn_effect)
+    {
+        if next_card_turn_effect == prev_card_turn_effect {
+            return true;
+        }
+    }
+    // If both cause drawing cards
+    if next_card.draw_effect.is_some() && prev_card.draw_effect.is_some() {
+        return true;
+    }
+    false
+}
+
+/// Whether `next_card` can be stacked on a draw card to pass its penalty on. It has to draw at
+/// least as many cards as the card it's stacked on.
+pub fn can_stack_card(prev_card: Option<&Card>, next_card: &Card) -> bool {
+    match (prev_card.and_then(|prev_card| prev_card.draw_effect), next_card.draw_effect) {
+        (Some(DrawEffect::Draw(prev_num_cards)), Some(DrawEffect::Draw(next_num_cards))) => {
+            next_num_cards >= prev_num_cards
+        }
+        _ => false,
+    }
+}
+
+pub fn create_deck() -> Vec<Card> {
+    let mut deck: Vec<Card> = Vec::new();
+    // There are 108 cards in a standard Uno deck.
     for i in 0..108 {
         deck.push({
             let mut new_card = Card::default();
@@ -465,7 +1514,105 @@ pub fn create_deck() -> Vec<Card> {
             new_card
         });
     }
-    return deck;
+    deck
+}
+
+/// Decides how Uno Flip's light and dark faces are paired up.
+const FLIP_DECK_PRINTING: u64 = 1;
+
+/// Uno Flip has 112 two faced cards. Every deck is printed the same way, so the light and dark
+/// faces are mixed up into pairs the same way every time.
+pub fn create_flip_deck() -> Vec<Card> {
+    let light_colors = Color::for_side(Side::Light);
+    let dark_colors = Color::for_side(Side::Dark);
+
+    let mut light_faces: Vec<Card> = Vec::new();
+    let mut dark_faces: Vec<Card> = Vec::new();
+    for (light_color, dark_color) in light_colors.iter().zip(dark_colors.iter()) {
+        // Each color has two of every card numbered 1-9 and two of each action card.
+        for _ in 0..2 {
+            for number in 1..=9 {
+                light_faces.push(Card { number: Some(number), color: Some(*light_color), ..Card::default() });
+                dark_faces.push(Card { number: Some(number), color: Some(*dark_color), ..Card::default() });
+            }
+            light_faces.push(Card { draw_effect: Some(DrawEffect::Draw(1)), color: Some(*light_color), ..Card::default() });
+            light_faces.push(Card { turn_effect: Some(TurnEffect::Reverse), color: Some(*light_color), ..Card::default() });
+            light_faces.push(Card { turn_effect: Some(TurnEffect::Skip), color: Some(*light_color), ..Card::default() });
+            light_faces.push(Card { turn_effect: Some(TurnEffect::Flip), color: Some(*light_color), ..Card::default() });
+            dark_faces.push(Card { draw_effect: Some(DrawEffect::Draw(5)), color: Some(*dark_color), ..Card::default() });
+            dark_faces.push(Card { turn_effect: Some(TurnEffect::Reverse), color: Some(*dark_color), ..Card::default() });
+            dark_faces.push(Card { turn_effect: Some(TurnEffect::SkipEveryone), color: Some(*dark_color), ..Card::default() });
+            dark_faces.push(Card { turn_effect: Some(TurnEffect::Flip), color: Some(*dark_color), ..Card::default() });
+        }
+    }
+    // 4 wilds and 4 wild draw cards on each side.
+    for _ in 0..4 {
+        light_faces.push(Card { wild: true, ..Card::default() });
+        light_faces.push(Card { wild: true, draw_effect: Some(DrawEffect::Draw(2)), ..Card::default() });
+        dark_faces.push(Card { wild: true, ..Card::default() });
+        dark_faces.push(Card { wild: true, draw_effect: Some(DrawEffect::DrawColor), ..Card::default() });
+    }
+
+    dark_faces.shuffle(&mut StdRng::seed_from_u64(FLIP_DECK_PRINTING));
+    light_faces
+        .into_iter()
+        .zip(dark_faces)
+        .map(|(light, dark)| Card::two_faced(light, dark))
+        .collect()
+}
+
+/// Newer decks have 112 cards: the classic cards, one wild shuffle hands card, and three blank
+/// customizable wilds.
+pub fn create_modern_deck() -> Vec<Card> {
+    let mut deck = create_deck();
+    deck.push(Card { wild: true, turn_effect: Some(TurnEffect::ShuffleHands), ..Card::default() });
+    for _ in 0..3 {
+        deck.push(Card { wild: true, turn_effect: Some(TurnEffect::Custom), ..Card::default() });
+    }
+    deck
+}
+
+/// The No Mercy deck. The wild color roulette cards aren't supported, which leaves 156 cards.
+pub fn create_no_mercy_deck() -> Vec<Card> {
+    let mut deck: Vec<Card> = Vec::new();
+    for color in Color::for_side(Side::Light) {
+        let color = Some(color);
+        // Two of every number 0-9.
+        for _ in 0..2 {
+            for number in 0..=9 {
+                deck.push(Card { number: Some(number), color, ..Card::default() });
+            }
+        }
+        for _ in 0..3 {
+            deck.push(Card { turn_effect: Some(TurnEffect::Skip), color, ..Card::default() });
+            deck.push(Card { turn_effect: Some(TurnEffect::Reverse), color, ..Card::default() });
+            deck.push(Card { turn_effect: Some(TurnEffect::DiscardAll), color, ..Card::default() });
+        }
+        for _ in 0..2 {
+            deck.push(Card { draw_effect: Some(DrawEffect::Draw(2)), color, ..Card::default() });
+            deck.push(Card { draw_effect: Some(DrawEffect::Draw(4)), color, ..Card::default() });
+            deck.push(Card { turn_effect: Some(TurnEffect::SkipEveryone), color, ..Card::default() });
+        }
+    }
+    for _ in 0..8 {
+        deck.push(Card { wild: true, turn_effect: Some(TurnEffect::Reverse), draw_effect: Some(DrawEffect::Draw(4)), ..Card::default() });
+    }
+    for _ in 0..4 {
+        deck.push(Card { wild: true, draw_effect: Some(DrawEffect::Draw(6)), ..Card::default() });
+        deck.push(Card { wild: true, draw_effect: Some(DrawEffect::Draw(10)), ..Card::default() });
+    }
+    deck
+}
+
+/// Puts every card in the discard pile into the deck, with wilds going back to having no color.
+fn return_discard_to_deck(deck: &mut Vec<Card>, discard: &mut Vec<Card>) {
+    for _ in 0..discard.len() {
+        let mut card = discard.pop().unwrap();
+        if card.wild && card.color.is_some() {
+            card.color = None;
+        }
+        deck.push(card);
+    }
 }
 
 fn draw_cards(
@@ -473,6 +1620,7 @@ fn draw_cards(
     num_to_draw: i32,
     deck: &mut Vec<Card>,
     discard: &mut Vec<Card>,
+    rng: &mut impl Rng,
 ) {
     if num_to_draw <= 0 {
         return;
@@ -488,15 +1636,8 @@ fn draw_cards(
     }
 
     if deck.len() < num_to_draw as usize {
-        for _ in 0..discard.len() {
-            let mut card = discard.pop().unwrap();
-            if card.wild && card.color.is_some() {
-                card.color = None;
-            }
-            deck.push(card);
-        }
-        let mut rng = thread_rng();
-        deck.shuffle(&mut rng);
+        return_discard_to_deck(deck, discard);
+        deck.shuffle(rng);
     }
 
     for _ in 0..num_to_draw {
@@ -507,13 +1648,52 @@ fn draw_cards(
 #[derive(Default)]
 pub struct Player {
     hand: Vec<Card>,
-    ai: bool
+    /// What a computer player uses to pick its moves. Humans don't have one.
+    strategy: Option<Box<dyn Strategy>>,
+    status: PlayerStatus,
+    team: Option<i32>,
+    /// The last color this player signaled to their team.
+    signal: Option<Color>,
+    signaled_this_turn: bool,
+    drew_this_turn: bool,
+    /// Whether the player called Uno this turn.
+    called_uno: bool,
+    /// Set when the player got down to one card without calling Uno, until they're challenged or
+    /// their next turn starts.
+    missed_uno_call: bool,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerStatus {
+    #[default]
+    Playing,
+    /// Knocked out of the game by the No Mercy mercy rule.
+    Eliminated,
+    /// Ran out of cards in a last player standing game.
+    Finished,
 }
 
+#[derive(PartialEq)]
 enum Command {
     PickCardToPlay(i32),
     DrawCard,
     PickWildCardColor(Color),
+    PickPlayerToSwapWith(i32),
+    Pass,
+    CallUno,
+    Challenge(usize),
+    SignalColor(Color),
+    /// Show why the computer players made their last moves.
+    Explain,
+    /// Ask what move to make.
+    Hint,
+}
+
+impl Player {
+    fn is_ai(&self) -> bool {
+        self.strategy.is_some()
+    }
 }
 
 #[cfg(test)]
@@ -531,15 +1711,17 @@ mod tests {
         }
 
         #[test]
+        #[allow(clippy::bool_assert_comparison)]
         fn players_alternate_between_human_and_ai() {
             let uno = Uno::new(2,2);
-            assert_eq!(uno.players[0].ai, false);
-            assert_eq!(uno.players[1].ai, true);
-            assert_eq!(uno.players[2].ai, false);
-            assert_eq!(uno.players[3].ai, true);
+            assert_eq!(uno.players[0].is_ai(), false);
+            assert_eq!(uno.players[1].is_ai(), true);
+            assert_eq!(uno.players[2].is_ai(), false);
+            assert_eq!(uno.players[3].is_ai(), true);
         }
     }
 
+    #[allow(clippy::field_reassign_with_default)]
     mod can_play_card {
         use crate::{
             card::{Card, Color, DrawEffect, TurnEffect},
@@ -632,6 +1814,7 @@ mod tests {
         use crate::{card::Card, game::create_deck};
 
         #[test]
+        #[allow(suspicious_double_ref_op)]
         fn contains_all_standard_cards() {
             fn create_cards_from_strs(strs: Vec<&str>) -> Vec<Card> {
                 strs.iter().map(|str| Card::from(str.clone())).collect()
@@ -774,6 +1957,14 @@ mod tests {
             let result = get_next_player_index(0, 3, TurnOrder::Backward);
             assert_eq!(result, 2)
         }
+
+        #[test]
+        fn skips_players_who_are_out() {
+            let mut players = vec![Player::default(), Player::default(), Player::default()];
+            players[1].status = PlayerStatus::Eliminated;
+            let result = get_next_active_player_index(0, &players, TurnOrder::Forward);
+            assert_eq!(result, 2);
+        }
     }
 
     mod draw_cards {
@@ -784,17 +1975,18 @@ mod tests {
             let mut hand: Vec<Card> = Vec::new();
             let mut deck = vec![Card::default(), Card::default(), Card::default()];
             let mut discard: Vec<Card> = Vec::new();
-            draw_cards(&mut hand, 2, &mut deck, &mut discard);
+            draw_cards(&mut hand, 2, &mut deck, &mut discard, &mut thread_rng());
             assert!(hand.len() == 2);
             assert!(deck.len() == 1);
         }
 
         #[test]
+        #[allow(clippy::len_zero)]
         fn moves_cards_from_discard_into_deck_if_deck_doesnt_have_enough() {
             let mut hand: Vec<Card> = Vec::new();
             let mut deck: Vec<Card> = Vec::new();
             let mut discard = vec![Card::default(), Card::default(), Card::default()];
-            draw_cards(&mut hand, 2, &mut deck, &mut discard);
+            draw_cards(&mut hand, 2, &mut deck, &mut discard, &mut thread_rng());
             assert!(hand.len() == 2);
             assert!(deck.len() == 1);
             assert!(discard.len() == 0);
@@ -806,16 +1998,17 @@ mod tests {
             let mut hand: Vec<Card> = Vec::new();
             let mut deck = vec![Card::default()];
             let mut discard = vec![Card::default()];
-            draw_cards(&mut hand, 3, &mut deck, &mut discard);
+            draw_cards(&mut hand, 3, &mut deck, &mut discard, &mut thread_rng());
         }
 
         #[test]
+        #[allow(clippy::get_first)]
         fn resets_wild_card_color_when_cards_move_from_discard_to_deck() {
             let mut hand: Vec<Card> = Vec::new();
             let mut deck: Vec<Card> = Vec::new();
             let old_wild_card = Card::from("wild blue");
             let mut discard = vec![old_wild_card];
-            draw_cards(&mut hand, 1, &mut deck, &mut discard);
+            draw_cards(&mut hand, 1, &mut deck, &mut discard, &mut thread_rng());
             let drawn_card = hand.get(0).unwrap();
             assert!(drawn_card.color.is_none());
         }
@@ -835,7 +2028,7 @@ mod tests {
             let mut next_player = Player::default();
             let draw_effect = DrawEffect::Draw(2);
 
-            play_card_draw_effect(&draw_effect, &mut next_player, &mut deck, &mut discard);
+            play_card_draw_effect(&draw_effect, None, &mut next_player, &mut deck, &mut discard, &mut thread_rng());
 
             assert_eq!(deck.len(), 1);
             assert_eq!(next_player.hand.len(), 2);
@@ -866,44 +2059,510 @@ mod tests {
         }
     }
 
-    mod automate_current_player_turn {
+    mod flip {
         use super::super::*;
 
         #[test]
-        fn player_plays_valid_card_in_hand() {
-            let mut uno = Uno::new(2,0);
-            let last_played_card = Card::from("green 5");
-            uno.discard.push(last_played_card);
-            let bad_card_1 = Card::from("red 1");
-            let bad_card_2 = Card::from("yellow 2");
-            let valid_card = Card::from("green 3");
-            uno.players[0].hand = vec![bad_card_1, valid_card, bad_card_2];
+        fn playing_a_flip_card_turns_over_every_card() {
+            let mut uno = Uno::with_rules(2, 0, Rules { mode: GameMode::Flip, ..Rules::default() });
+            uno.players[1].hand = vec![Card::from("red 1 / pink 2")];
+            uno.deck = vec![Card::from("blue 3 / teal 4")];
 
-            uno.automate_current_player_turn();
-            
-            assert_eq!(uno.players[0].hand.len(), 2);
-            let played_card = uno.discard.last().unwrap();
-            assert_eq!(played_card.color, Some(Color::Green));
-            assert_eq!(played_card.number, Some(3));
+            uno.play_card(Card::from("red flip / orange flip"));
+
+            assert_eq!(uno.side, Side::Dark);
+            assert!(uno.players[1].hand[0] == Card::from("pink 2 / red 1"));
+            assert!(uno.deck[0] == Card::from("teal 4 / blue 3"));
+            assert!(*uno.discard.last().unwrap() == Card::from("orange flip / red flip"));
         }
 
         #[test]
-        fn player_plays_wild_card_in_hand() {
-            let mut uno = Uno::new(2,0);
-            let wild_card = Card::from("wild");
-            uno.players[0].hand = vec![wild_card];
+        fn after_player_1_plays_a_skip_everyone_player_1_goes_again() {
+            let mut uno = Uno::with_rules(4, 0, Rules { mode: GameMode::Flip, ..Rules::default() });
 
-            uno.automate_current_player_turn();
-            
-            let played_card = uno.discard.last().unwrap();
-            assert!(played_card.wild);
-            assert!(played_card.color.is_some());
+            uno.play_card(Card::from("teal skipeveryone"));
+
+            assert_eq!(uno.current_player_index, 0);
         }
 
         #[test]
-        fn player_draws_when_no_valid_cards_in_hand_and_plays_next_valid_card() {
-            let mut uno = Uno::new(2,0);
-            let last_played_card = Card::from("green 5");
+        fn draw_color_makes_next_player_draw_until_they_get_the_color() {
+            let mut uno = Uno::with_rules(2, 0, Rules { mode: GameMode::Flip, ..Rules::default() });
+            uno.players[1].hand = vec![];
+            uno.deck = vec![Card::from("pink 1"), Card::from("purple 2"), Card::from("teal 3")];
+            let mut wild_draw_color = Card::from("wild drawcolor");
+            wild_draw_color.color = Some(Color::Purple);
+
+            uno.play_card(wild_draw_color);
+
+            assert_eq!(uno.players[1].hand.len(), 2);
+            assert_eq!(uno.players[1].hand.last().unwrap().color, Some(Color::Purple));
+            assert_eq!(uno.deck.len(), 1);
+        }
+
+        #[test]
+        fn flip_deck_has_112_two_faced_cards() {
+            let deck = create_flip_deck();
+            assert_eq!(deck.len(), 112);
+            assert!(deck.iter().all(|card| card.flip_side.is_some()));
+            let count_light = |light: &str| deck.iter().filter(|card| card.face() == Card::from(light).face()).count();
+            assert_eq!(count_light("red 5"), 2);
+            assert_eq!(count_light("green draw1"), 2);
+            assert_eq!(count_light("yellow flip"), 2);
+            assert_eq!(count_light("wild draw2"), 4);
+            let mut flipped_deck = deck.clone();
+            flipped_deck.iter_mut().for_each(Card::flip);
+            let count_dark = |dark: &str| flipped_deck.iter().filter(|card| card.face() == Card::from(dark).face()).count();
+            assert_eq!(count_dark("teal draw5"), 2);
+            assert_eq!(count_dark("purple skipeveryone"), 2);
+            assert_eq!(count_dark("wild drawcolor"), 4);
+        }
+
+        #[test]
+        fn can_only_pick_wild_colors_from_the_showing_side() {
+            let mut uno = Uno::with_rules(1, 1, Rules { mode: GameMode::Flip, ..Rules::default() });
+            uno.players[0].hand.insert(0, Card::from("wild"));
+            uno.input(Input::Number(1));
+
+            uno.input(Input::Text("pink".to_string()));
+
+            assert!(uno.discard.is_empty());
+            assert_eq!(uno.wild_card_index_to_pick_color_for, Some(1));
+        }
+    }
+
+    mod no_mercy {
+        use super::super::*;
+
+        fn no_mercy_game(player_count: i32, ai_count: i32) -> Uno {
+            Uno::with_rules(player_count, ai_count, Rules { mode: GameMode::NoMercy, ..Rules::default() })
+        }
+
+        #[test]
+        fn draw_cards_stack_up_instead_of_being_drawn_right_away() {
+            let mut uno = no_mercy_game(2, 0);
+
+            uno.play_card(Card::from("red draw2"));
+
+            assert_eq!(uno.players[1].hand.len(), 7);
+            assert_eq!(uno.pending_draw, 2);
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn can_stack_draw_cards_of_equal_or_higher_value() {
+            let draw_4 = Card::from("red draw4");
+            assert!(can_stack_card(Some(&draw_4), &Card::from("blue draw4")));
+            assert!(can_stack_card(Some(&draw_4), &Card::from("wild draw10")));
+            assert!(!can_stack_card(Some(&draw_4), &Card::from("red draw2")));
+            assert!(!can_stack_card(Some(&draw_4), &Card::from("red skip")));
+        }
+
+        #[test]
+        fn ai_stacks_a_draw_card_when_it_has_one() {
+            let mut uno = no_mercy_game(2, 0);
+            uno.play_card(Card::from("red draw2"));
+            uno.players[1].hand = vec![Card::from("red 3"), Card::from("blue draw4")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.pending_draw, 6);
+            assert_eq!(uno.players[1].hand.len(), 1);
+            assert_eq!(uno.current_player_index, 0);
+        }
+
+        #[test]
+        fn ai_takes_the_penalty_and_loses_its_turn_when_it_cant_stack() {
+            let mut uno = no_mercy_game(3, 0);
+            uno.play_card(Card::from("red draw2"));
+            uno.play_card(Card::from("red draw4"));
+            uno.players[2].hand = vec![Card::from("red 3")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.pending_draw, 0);
+            assert_eq!(uno.players[2].hand.len(), 7);
+            assert_eq!(uno.current_player_index, 0);
+        }
+
+        #[test]
+        fn human_can_only_stack_while_a_penalty_is_pending() {
+            let mut uno = no_mercy_game(2, 0);
+            uno.play_card(Card::from("red draw2"));
+            uno.players[1].hand.insert(0, Card::from("red 5"));
+
+            uno.input(Input::Number(1));
+
+            assert!(uno.ui.error.is_some());
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn players_holding_25_cards_are_knocked_out() {
+            let mut uno = no_mercy_game(3, 0);
+            uno.players[1].hand = vec![Card::from("red 1"); 23];
+            uno.play_card(Card::from("red draw2"));
+            let deck_size_before = uno.deck.len();
+
+            uno.take_draw_penalty();
+
+            assert_eq!(uno.players[1].status, PlayerStatus::Eliminated);
+            assert!(uno.players[1].hand.is_empty());
+            assert_eq!(uno.deck.len(), deck_size_before + 23);
+            assert_eq!(uno.current_player_index, 2);
+            assert!(!uno.game_over());
+        }
+
+        #[test]
+        fn knocked_out_players_are_skipped() {
+            let mut uno = no_mercy_game(3, 0);
+            uno.players[1].status = PlayerStatus::Eliminated;
+
+            uno.play_card(Card::from("red 1"));
+
+            assert_eq!(uno.current_player_index, 2);
+        }
+
+        #[test]
+        fn last_player_left_wins() {
+            let mut uno = no_mercy_game(3, 0);
+            uno.players[0].status = PlayerStatus::Eliminated;
+            uno.players[0].hand.clear();
+            uno.players[2].status = PlayerStatus::Eliminated;
+            uno.players[2].hand.clear();
+
+            assert!(uno.game_over());
+            assert_eq!(uno.winning_player_index(), Some(1));
+        }
+
+        #[test]
+        fn discard_all_discards_every_card_of_its_color() {
+            let mut uno = no_mercy_game(2, 0);
+            uno.players[0].hand = vec![Card::from("blue 1"), Card::from("green 2"), Card::from("blue skip")];
+
+            uno.play_card(Card::from("blue discardall"));
+
+            assert!(uno.players[0].hand == vec![Card::from("green 2")]);
+            assert_eq!(uno.discard.len(), 3);
+            assert!(*uno.discard.last().unwrap() == Card::from("blue discardall"));
+        }
+
+        #[test]
+        fn no_mercy_deck_has_156_cards() {
+            let deck = create_no_mercy_deck();
+            assert_eq!(deck.len(), 156);
+            let count = |card: &str| deck.iter().filter(|deck_card| **deck_card == Card::from(card)).count();
+            assert_eq!(count("red 0"), 2);
+            assert_eq!(count("blue discardall"), 3);
+            assert_eq!(count("green skipeveryone"), 2);
+            assert_eq!(count("wild reverse draw4"), 8);
+            assert_eq!(count("wild draw10"), 4);
+        }
+    }
+
+    mod card_effects {
+        use super::super::*;
+
+        fn modern_game(player_count: i32, custom_wild_effect: Option<Effect>) -> Uno {
+            Uno::with_rules(player_count, 0, Rules { mode: GameMode::Modern, custom_wild_effect, ..Rules::default() })
+        }
+
+        #[test]
+        fn swap_hands_swaps_with_the_player_with_the_fewest_cards() {
+            let mut uno = modern_game(3, None);
+            uno.players[0].hand = vec![Card::from("red 1"), Card::from("red 2")];
+            uno.players[1].hand = vec![Card::from("blue 1"), Card::from("blue 2"), Card::from("blue 3")];
+            uno.players[2].hand = vec![Card::from("green 1")];
+
+            uno.play_card(Card::from("wild swaphands"));
+
+            assert!(uno.players[0].hand == vec![Card::from("green 1")]);
+            assert_eq!(uno.players[2].hand.len(), 2);
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn swap_hands_swaps_with_the_picked_player() {
+            let mut uno = modern_game(3, None);
+            uno.players[0].hand = vec![Card::from("red 1")];
+            uno.players[1].hand = vec![Card::from("blue 1"), Card::from("blue 2")];
+
+            uno.play_card_targeting(Card::from("wild swaphands"), Some(1));
+
+            assert_eq!(uno.players[0].hand.len(), 2);
+            assert!(uno.players[1].hand == vec![Card::from("red 1")]);
+        }
+
+        #[test]
+        fn shuffle_hands_deals_everyones_cards_back_out_starting_with_the_next_player() {
+            let mut uno = modern_game(3, None);
+            uno.players[0].hand = vec![Card::from("red 1")];
+            uno.players[1].hand = vec![Card::from("blue 1"), Card::from("blue 2")];
+            uno.players[2].hand = vec![Card::from("green 1"), Card::from("green 2")];
+
+            uno.play_card(Card::from("wild shufflehands"));
+
+            assert_eq!(uno.players[0].hand.len(), 1);
+            assert_eq!(uno.players[1].hand.len(), 2);
+            assert_eq!(uno.players[2].hand.len(), 2);
+        }
+
+        #[test]
+        fn customizable_wilds_do_what_the_rules_say() {
+            let mut uno = modern_game(3, Some(Effect::Skip));
+
+            uno.play_card(Card::from("wild custom"));
+
+            assert_eq!(uno.current_player_index, 2);
+        }
+
+        #[test]
+        fn customizable_wilds_are_plain_wilds_without_a_rule() {
+            let mut uno = modern_game(3, None);
+
+            uno.play_card(Card::from("wild custom"));
+
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn human_picks_who_to_swap_hands_with() {
+            let mut uno = modern_game(3, None);
+            uno.players[0].hand = vec![Card::from("wild swaphands"), Card::from("red 1")];
+            uno.players[2].hand = vec![Card::from("blue 1"), Card::from("blue 2"), Card::from("blue 3")];
+
+            uno.input(Input::Number(1));
+            uno.input(Input::Text("r".to_string()));
+            uno.input(Input::Number(3));
+
+            assert_eq!(uno.players[0].hand.len(), 3);
+            assert!(uno.players[2].hand == vec![Card::from("red 1")]);
+            assert_eq!(uno.discard.last().unwrap().color, Some(Color::Red));
+        }
+
+        #[test]
+        fn modern_deck_has_112_cards() {
+            let deck = create_modern_deck();
+            assert_eq!(deck.len(), 112);
+            assert_eq!(deck.iter().filter(|card| **card == Card::from("wild shufflehands")).count(), 1);
+            assert_eq!(deck.iter().filter(|card| **card == Card::from("wild custom")).count(), 3);
+        }
+    }
+
+    mod teams {
+        use super::super::*;
+
+        fn team_game(player_count: i32, ai_count: i32) -> Uno {
+            Uno::with_rules(player_count, ai_count, Rules { teams: Some(2), team_signals: true, ..Rules::default() })
+        }
+
+        #[test]
+        fn partners_sit_across_from_each_other() {
+            let uno = team_game(2, 2);
+            assert_eq!(uno.players[0].team, uno.players[2].team);
+            assert_eq!(uno.players[1].team, uno.players[3].team);
+            assert_ne!(uno.players[0].team, uno.players[1].team);
+        }
+
+        #[test]
+        #[should_panic]
+        fn players_have_to_split_evenly_into_teams() {
+            team_game(2, 1);
+        }
+
+        #[test]
+        fn uneven_or_single_teams_are_refused() {
+            let rules = |teams| Rules { teams: Some(teams), ..Rules::default() };
+            assert!(rules(2).check_teams(4).is_ok());
+            assert!(rules(3).check_teams(4).is_err());
+            assert!(rules(1).check_teams(4).is_err());
+            assert!(Rules::default().check_teams(3).is_ok());
+        }
+
+        #[test]
+        fn team_scores_the_cards_left_in_opponents_hands() {
+            let mut uno = team_game(4, 0);
+            uno.players[0].hand = vec![];
+            uno.players[1].hand = vec![Card::from("red 5")];
+            uno.players[2].hand = vec![Card::from("wild")];
+            uno.players[3].hand = vec![Card::from("blue skip")];
+
+            assert!(uno.game_over());
+            assert_eq!(uno.winning_player_index(), Some(0));
+            assert_eq!(uno.points_won(), 25);
+        }
+
+        #[test]
+        fn game_is_over_once_only_one_team_is_left() {
+            let mut uno = Uno::with_rules(4, 0, Rules { mode: GameMode::NoMercy, teams: Some(2), ..Rules::default() });
+            uno.players[1].status = PlayerStatus::Eliminated;
+            assert!(!uno.game_over());
+            uno.players[3].status = PlayerStatus::Eliminated;
+            assert!(uno.game_over());
+        }
+
+        #[test]
+        fn ai_avoids_making_its_partner_draw() {
+            let mut uno = team_game(4, 0);
+            // With player 2 knocked out, player 1's partner is up next.
+            uno.players[1].status = PlayerStatus::Eliminated;
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red draw2"), Card::from("red 7"), Card::from("blue 3")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red 7"));
+        }
+
+        #[test]
+        fn ai_picks_the_color_its_partner_signaled() {
+            let mut uno = team_game(2, 2);
+            uno.players[2].signal = Some(Color::Yellow);
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 1"), Card::from("red 2")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.discard.last().unwrap().color, Some(Color::Yellow));
+        }
+
+        #[test]
+        fn humans_can_signal_once_per_turn() {
+            let mut uno = team_game(2, 2);
+
+            uno.input(Input::Text("s blue".to_string()));
+            uno.input(Input::Text("s red".to_string()));
+
+            assert_eq!(uno.players[0].signal, Some(Color::Blue));
+            assert!(uno.ui.error.is_some());
+        }
+    }
+
+    mod last_player_standing {
+        use super::super::*;
+
+        fn last_player_standing_game(player_count: i32) -> Uno {
+            Uno::with_rules(player_count, 0, Rules { last_player_standing: true, ..Rules::default() })
+        }
+
+        #[test]
+        fn player_who_goes_out_leaves_and_the_game_goes_on() {
+            let mut uno = last_player_standing_game(3);
+            uno.players[0].hand = vec![];
+
+            uno.play_card(Card::from("red 1"));
+
+            assert_eq!(uno.players[0].status, PlayerStatus::Finished);
+            assert!(!uno.game_over());
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn finished_players_are_skipped() {
+            let mut uno = last_player_standing_game(3);
+            uno.players[0].hand = vec![];
+            uno.play_card(Card::from("red 1"));
+
+            uno.play_card(Card::from("red 2"));
+            uno.play_card(Card::from("red 3"));
+
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn player_who_goes_out_with_skip_everyone_doesnt_go_again() {
+            let mut uno = last_player_standing_game(3);
+            uno.players[0].hand = vec![];
+
+            uno.play_card(Card::from("red skipeveryone"));
+
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn going_out_with_a_reverse_passes_the_turn_backwards() {
+            let mut uno = last_player_standing_game(3);
+            uno.players[0].hand = vec![];
+
+            uno.play_card(Card::from("red reverse"));
+
+            assert_eq!(uno.current_player_index, 2);
+        }
+
+        #[test]
+        fn game_ends_when_one_player_is_left_holding_cards() {
+            let mut uno = last_player_standing_game(3);
+            uno.players[0].hand = vec![];
+            uno.play_card(Card::from("red 1"));
+            uno.players[1].hand = vec![];
+            uno.play_card(Card::from("red 2"));
+
+            assert!(uno.game_over());
+            assert_eq!(uno.finishing_order(), vec![0, 1, 2]);
+            assert_eq!(uno.winning_player_index(), Some(0));
+        }
+
+        #[test]
+        fn knocked_out_players_place_last() {
+            let mut uno = Uno::with_rules(4, 0, Rules { mode: GameMode::NoMercy, last_player_standing: true, ..Rules::default() });
+            uno.players[3].hand = vec![Card::from("red 1"); 25];
+            uno.apply_mercy_rule(3);
+            uno.players[0].hand = vec![];
+            uno.play_card(Card::from("red 1"));
+            uno.players[1].hand = vec![Card::from("red 1"); 3];
+            uno.players[2].hand = vec![Card::from("red 1"); 2];
+
+            assert_eq!(uno.finishing_order(), vec![0, 2, 1, 3]);
+        }
+    }
+
+    mod automate_current_player_turn {
+        use super::super::*;
+
+        #[test]
+        fn player_plays_valid_card_in_hand() {
+            let mut uno = Uno::new(2,0);
+            let last_played_card = Card::from("green 5");
+            uno.discard.push(last_played_card);
+            let bad_card_1 = Card::from("red 1");
+            let bad_card_2 = Card::from("yellow 2");
+            let valid_card = Card::from("green 3");
+            uno.players[0].hand = vec![bad_card_1, valid_card, bad_card_2];
+
+            uno.automate_current_player_turn();
+            
+            assert_eq!(uno.players[0].hand.len(), 2);
+            let played_card = uno.discard.last().unwrap();
+            assert_eq!(played_card.color, Some(Color::Green));
+            assert_eq!(played_card.number, Some(3));
+        }
+
+        #[test]
+        fn player_plays_wild_card_in_hand() {
+            let mut uno = Uno::new(2,0);
+            let wild_card = Card::from("wild");
+            uno.players[0].hand = vec![wild_card];
+
+            uno.automate_current_player_turn();
+            
+            let played_card = uno.discard.last().unwrap();
+            assert!(played_card.wild);
+            assert!(played_card.color.is_some());
+        }
+
+        #[test]
+        fn player_picks_the_wild_color_it_holds_the_most_of() {
+            let mut uno = Uno::new(2,0);
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 1"), Card::from("blue 2"), Card::from("blue 3")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.discard.last().unwrap().color, Some(Color::Blue));
+        }
+
+        #[test]
+        fn player_draws_when_no_valid_cards_in_hand_and_plays_next_valid_card() {
+            let mut uno = Uno::new(2,0);
+            let last_played_card = Card::from("green 5");
             uno.discard.push(last_played_card);
             let bad_hand_card_1 = Card::from("red 1");
             let bad_hand_card_2 = Card::from("yellow 2");
@@ -912,13 +2571,681 @@ mod tests {
             let valid_deck_card = Card::from("green 4");
             uno.deck = vec![valid_deck_card, bad_deck_card];
 
-            uno.automate_current_player_turn();
-            
+            uno.automate_current_player_turn();
+            
+            assert_eq!(uno.players[0].hand.len(), 3);
+            assert_eq!(uno.deck.len(), 0);
+            let played_card = uno.discard.last().unwrap();
+            assert_eq!(played_card.color, Some(Color::Green));
+            assert_eq!(played_card.number, Some(4));
+        }
+    }
+
+    mod legal_actions {
+        use super::super::*;
+
+        #[test]
+        fn lists_each_playable_card_and_drawing() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("red 2"), Card::from("green 1")];
+
+            let actions = uno.legal_actions(0);
+
+            assert_eq!(actions, vec![
+                Action::Play { card_index: 1, color: None, swap_with: None },
+                Action::Play { card_index: 2, color: None, swap_with: None },
+                Action::Draw,
+            ]);
+        }
+
+        #[test]
+        fn wilds_can_be_played_as_any_color_on_the_side_thats_showing() {
+            let mut uno = Uno::new(2, 0);
+            uno.players[0].hand = vec![Card::from("wild")];
+
+            let actions = uno.legal_actions(0);
+
+            assert_eq!(actions.len(), 5);
+            for color in Color::for_side(Side::Light) {
+                assert!(actions.contains(&Action::Play { card_index: 0, color: Some(color), swap_with: None }));
+            }
+        }
+
+        #[test]
+        fn swap_hands_cards_can_swap_with_anyone_still_in() {
+            let mut uno = Uno::with_rules(3, 0, Rules { mode: GameMode::Modern, ..Rules::default() });
+            uno.players[2].status = PlayerStatus::Eliminated;
+            uno.players[0].hand = vec![Card::from("wild swaphands")];
+
+            let actions = uno.legal_actions(0);
+
+            assert!(actions.contains(&Action::Play { card_index: 0, color: Some(Color::Red), swap_with: Some(1) }));
+            assert!(!actions.iter().any(|action| matches!(action, Action::Play { swap_with: Some(2), .. })));
+        }
+
+        #[test]
+        fn every_legal_action_can_be_applied() {
+            let uno = Uno::new(2, 1);
+            for action in uno.legal_actions(0) {
+                let hands = uno.players.iter().map(|player| player.hand.clone()).collect();
+                let mut uno = Uno::from_position(&uno.view(0), hands, uno.deck.clone(), Vec::new());
+                assert!(uno.apply_action(0, action).is_ok());
+            }
+        }
+
+        #[test]
+        fn cant_draw_when_there_are_no_cards_left() {
+            let mut uno = Uno::new(2, 0);
+            uno.deck.clear();
+
+            assert!(!uno.legal_actions(0).contains(&Action::Draw));
+            assert!(uno.apply_action(0, Action::Draw).is_err());
+        }
+
+        #[test]
+        fn can_only_pass_after_drawing() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("green 4"), Card::from("yellow 5")];
+
+            assert!(!uno.legal_actions(0).contains(&Action::Pass));
+            uno.apply_action(0, Action::Draw).unwrap();
+            assert!(uno.legal_actions(0).contains(&Action::Pass));
+        }
+
+        #[test]
+        fn can_pass_when_theres_nothing_left_to_draw() {
+            let mut uno = Uno::new(2, 0);
+            uno.deck.clear();
+            uno.discard.clear();
+
+            let actions = uno.legal_actions(0);
+
+            assert!(actions.contains(&Action::Pass));
+            assert!(!actions.contains(&Action::Draw));
+        }
+
+        #[test]
+        fn can_call_uno_when_down_to_two_cards() {
+            let mut uno = Uno::new(2, 0);
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("green 4")];
+
+            assert!(uno.legal_actions(0).contains(&Action::CallUno));
+            uno.apply_action(0, Action::CallUno).unwrap();
+            assert!(!uno.legal_actions(0).contains(&Action::CallUno));
+        }
+
+        #[test]
+        fn players_whose_turn_it_isnt_can_only_challenge() {
+            let mut uno = Uno::new(3, 0);
+            uno.players[2].missed_uno_call = true;
+
+            assert_eq!(uno.legal_actions(1), vec![Action::Challenge(2)]);
+        }
+    }
+
+    mod apply_action {
+        use super::super::*;
+        use crate::profile::Tally;
+
+        #[test]
+        fn plays_the_card_at_the_index() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("red 2"), Card::from("green 4")];
+
+            let result = uno.apply_action(0, Action::Play { card_index: 1, color: None, swap_with: None });
+
+            assert!(result.is_ok());
+            assert!(*uno.discard.last().unwrap() == Card::from("red 2"));
+            assert_eq!(uno.current_player_index, 1);
+        }
+
+        #[test]
+        fn wilds_need_a_color() {
+            let mut uno = Uno::new(2, 0);
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 2")];
+
+            let result = uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None });
+
+            assert!(result.is_err());
+            assert_eq!(uno.players[0].hand.len(), 2);
+            assert_eq!(uno.current_player_index, 0);
+        }
+
+        #[test]
+        fn cant_play_cards_that_dont_match() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("red 2")];
+
+            let result = uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None });
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn plays_and_draws_are_recorded_in_the_history() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red draw2"), Card::from("red 2")];
+
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+
+            let recap = uno.turn_recap_since(0, 0);
+            assert_eq!(recap.played_cards.len(), 1);
+            assert!(recap.played_cards[0] == Card::from("red draw2"));
+            assert_eq!(uno.turn_recap_since(1, 0).drawn_cards, 2);
+        }
+
+        #[test]
+        fn skips_are_recorded_in_the_history() {
+            let mut uno = Uno::new(3, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red skip"), Card::from("red 2")];
+
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+
+            assert!(matches!(uno.history.last(), Some(Event::Skipped { player: 1 })));
+            assert_eq!(uno.turn_recap_since(0, 0).skipped_players, vec![2]);
+        }
+
+        #[test]
+        fn moves_are_added_to_the_players_profile() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 2")];
+
+            uno.apply_action(0, Action::Play { card_index: 1, color: None, swap_with: None }).unwrap();
+
+            let profile = &uno.profiles()[0];
+            assert_eq!(profile.holding_wilds, Tally { times: 1, out_of: 1 });
+            assert_eq!(profile.draws_on(Color::Red), Tally { times: 0, out_of: 1 });
+            assert_eq!(profile.uno_calls, Tally { times: 0, out_of: 1 });
+        }
+
+        #[test]
+        fn passing_ends_the_turn() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 3"), Card::from("green 4"), Card::from("yellow 5")];
+            uno.deck = vec![Card::from("blue 6")];
+
+            uno.apply_action(0, Action::Draw).unwrap();
+            uno.apply_action(0, Action::Pass).unwrap();
+
+            assert_eq!(uno.current_player_index, 1);
+            assert_eq!(uno.players[0].hand.len(), 4);
+        }
+
+        #[test]
+        fn players_who_dont_call_uno_can_be_challenged() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 2"), Card::from("green 4")];
+
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+            uno.apply_action(1, Action::Challenge(0)).unwrap();
+
             assert_eq!(uno.players[0].hand.len(), 3);
-            assert_eq!(uno.deck.len(), 0);
-            let played_card = uno.discard.last().unwrap();
-            assert_eq!(played_card.color, Some(Color::Green));
-            assert_eq!(played_card.number, Some(4));
+            assert!(!uno.legal_actions(1).contains(&Action::Challenge(0)));
+            assert_eq!(uno.turn_recap_since(1, 0).challenged_players, vec![1]);
+        }
+
+        #[test]
+        fn challenging_someone_else_doesnt_stop_a_player_being_challenged() {
+            let mut uno = Uno::new(3, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 2"), Card::from("green 4")];
+            uno.players[1].hand = vec![Card::from("red 3"), Card::from("blue 5")];
+
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+            uno.apply_action(1, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+            uno.apply_action(0, Action::Challenge(1)).unwrap();
+            uno.apply_action(2, Action::Challenge(0)).unwrap();
+
+            assert_eq!(uno.players[0].hand.len(), 3);
+            assert_eq!(uno.players[1].hand.len(), 3);
+        }
+
+        #[test]
+        fn challenges_only_draw_what_is_left() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard = vec![Card::from("red 1")];
+            uno.deck.clear();
+            uno.players[0].hand = vec![Card::from("red 2"), Card::from("green 4")];
+
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+            // Only the red 2 on top is left to draw.
+            uno.discard.remove(0);
+            uno.apply_action(1, Action::Challenge(0)).unwrap();
+
+            assert_eq!(uno.players[0].hand.len(), 2);
+            assert!(uno.deck.is_empty() && uno.discard.is_empty());
+            assert!(uno.history.iter().any(|event| matches!(event, Event::Drew { player: 0, count: 1, forced: true })));
+        }
+
+        #[test]
+        fn calling_uno_keeps_a_player_from_being_challenged() {
+            let mut uno = Uno::new(2, 0);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 2"), Card::from("green 4")];
+
+            uno.apply_action(0, Action::CallUno).unwrap();
+            uno.apply_action(0, Action::Play { card_index: 0, color: None, swap_with: None }).unwrap();
+
+            assert!(uno.apply_action(1, Action::Challenge(0)).is_err());
+            assert_eq!(uno.players[0].hand.len(), 1);
+        }
+    }
+
+    mod strategy {
+        use super::super::*;
+        use crate::view::PlayerView;
+
+        /// Always draws, however many cards it has to.
+        struct AlwaysDraw;
+
+        impl Strategy for AlwaysDraw {
+            fn choose_action(&mut self, view: &PlayerView) -> Action {
+                if view.hand.len() < 10 {
+                    Action::Draw
+                } else {
+                    Action::Play { card_index: view.playable_card_indexes()[0], color: Some(Color::Red), swap_with: None }
+                }
+            }
+        }
+
+        /// Asks for a card it doesn't have.
+        struct Cheater;
+
+        impl Strategy for Cheater {
+            fn choose_action(&mut self, _view: &PlayerView) -> Action {
+                Action::Play { card_index: 100, color: None, swap_with: None }
+            }
+        }
+
+        #[test]
+        fn players_use_their_own_strategy() {
+            let mut uno = Uno::new(1, 1);
+            uno.current_player_index = 1;
+            uno.discard.push(Card::from("red 1"));
+            uno.players[1].hand = vec![Card::from("red 2")];
+            uno.deck = vec![Card::from("wild"); 20];
+            uno.players[1].strategy = Some(Box::new(AlwaysDraw));
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.players[1].hand.len(), 9);
+            assert!(uno.players[1].is_ai());
+        }
+
+        #[test]
+        fn illegal_actions_are_replaced_with_a_basic_move() {
+            let mut uno = Uno::new(1, 1);
+            uno.current_player_index = 1;
+            uno.discard.push(Card::from("red 1"));
+            uno.players[1].hand = vec![Card::from("blue 3"), Card::from("red 2")];
+            uno.players[1].strategy = Some(Box::new(Cheater));
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red 2"));
+            assert_eq!(uno.current_player_index, 0);
+        }
+
+        #[test]
+        fn view_shows_hand_sizes_and_whos_next() {
+            let mut uno = Uno::new(3, 0);
+            uno.players[1].hand = vec![Card::from("red 1")];
+
+            let view = uno.view(0);
+
+            assert_eq!(view.hand_sizes, vec![7, 1, 7]);
+            assert_eq!(view.next_player, 1);
+            assert_eq!(view.previous_player, 2);
+        }
+
+        #[test]
+        fn view_hides_the_backs_of_a_players_own_cards() {
+            let mut uno = Uno::with_rules(2, 0, Rules { mode: GameMode::Flip, ..Rules::default() });
+            uno.players[0].hand = vec![Card::from("red 1 / teal skip")];
+            uno.players[1].hand = vec![Card::from("blue 2 / pink 3")];
+
+            let view = uno.view(0);
+
+            assert!(view.hand[0].flip_side.is_none());
+            assert!(view.backs[0].is_empty());
+            assert_eq!(view.backs[1], vec![Card::from("pink 3").face()]);
+        }
+
+        #[test]
+        fn view_hides_the_back_of_the_top_card() {
+            let mut uno = Uno::with_rules(2, 0, Rules { mode: GameMode::Flip, ..Rules::default() });
+            uno.discard = vec![Card::from("green 4 / orange skipeveryone")];
+
+            let view = uno.view(1);
+
+            assert_eq!(view.top_card.unwrap().face(), Card::from("green 4").face());
+            assert!(view.top_card.unwrap().flip_side.is_none());
+        }
+    }
+
+    mod difficulty {
+        use std::time::Duration;
+
+        use super::super::*;
+        use crate::ai::{Difficulty, ExpectiminimaxStrategy, MctsStrategy};
+
+        #[test]
+        fn easy_players_only_play_legal_cards() {
+            for _ in 0..20 {
+                let mut uno = Uno::new(2, 0);
+                uno.set_strategy(0, Difficulty::Easy.strategy().unwrap());
+                uno.discard.push(Card::from("red 1"));
+                uno.players[0].hand = vec![Card::from("blue 3"), Card::from("red 2"), Card::from("wild"), Card::from("green 4")];
+
+                uno.automate_current_player_turn();
+
+                let played_card = uno.discard.last().unwrap();
+                assert!(*played_card == Card::from("red 2") || played_card.wild);
+                assert_eq!(uno.players[0].hand.len(), 3);
+            }
+        }
+
+        #[test]
+        fn medium_players_save_their_wilds() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("wild draw4"), Card::from("wild"), Card::from("red 2")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red 2"));
+        }
+
+        #[test]
+        fn medium_players_get_rid_of_cards_worth_the_most_points() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 5"));
+            uno.players[0].hand = vec![Card::from("red 2"), Card::from("blue 5"), Card::from("red 9"), Card::from("green 1")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red 9"));
+        }
+
+        #[test]
+        fn medium_players_save_wild_draw_cards_for_players_about_to_go_out() {
+            let mut uno = Uno::new(3, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("wild draw4"), Card::from("blue 2")];
+            uno.players[1].hand = vec![Card::from("green 3"), Card::from("green 4")];
+
+            uno.automate_current_player_turn();
+
+            assert!(uno.discard.last().unwrap().wild);
+            assert_eq!(uno.players[1].hand.len(), 6);
+        }
+
+        #[test]
+        fn medium_players_skip_players_about_to_go_out() {
+            let mut uno = Uno::new(3, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 9"), Card::from("red skip"), Card::from("red 8")];
+            uno.players[1].hand = vec![Card::from("green 3")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red skip"));
+        }
+
+        #[test]
+        fn medium_players_reverse_play_away_from_the_leader() {
+            let mut uno = Uno::new(3, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 9"), Card::from("red reverse"), Card::from("blue 2")];
+            uno.players[1].hand = vec![Card::from("green 3"), Card::from("green 4"), Card::from("green 5")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red reverse"));
+            assert_eq!(uno.current_player_index, 2);
+        }
+
+        #[test]
+        fn medium_players_dont_reverse_play_toward_the_leader() {
+            let mut uno = Uno::new(3, 0);
+            uno.set_strategy(0, Difficulty::Medium.strategy().unwrap());
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 9"), Card::from("red reverse"), Card::from("blue 2")];
+            uno.players[2].hand = vec![Card::from("green 3"), Card::from("green 4"), Card::from("green 5")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("red 9"));
+        }
+
+        #[test]
+        fn hard_players_play_colors_the_next_player_drew_on() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Difficulty::Hard.strategy().unwrap());
+            uno.history = vec![
+                Event::Played { player: 0, card: Card::from("green 3") },
+                Event::Drew { player: 1, count: 1, forced: false },
+                Event::Played { player: 1, card: Card::from("yellow 5") },
+            ];
+            uno.discard.push(Card::from("yellow 5"));
+            uno.players[0].hand = vec![Card::from("green 5"), Card::from("blue 5")];
+
+            uno.automate_current_player_turn();
+
+            assert!(*uno.discard.last().unwrap() == Card::from("green 5"));
+        }
+
+        #[test]
+        fn hard_players_pick_wild_colors_the_next_player_drew_on() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Difficulty::Hard.strategy().unwrap());
+            uno.history = vec![
+                Event::Played { player: 0, card: Card::from("blue 3") },
+                Event::Drew { player: 1, count: 1, forced: false },
+                Event::Played { player: 1, card: Card::from("yellow 3") },
+            ];
+            uno.discard.push(Card::from("yellow 3"));
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 1"), Card::from("blue 1")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.discard.last().unwrap().color, Some(Color::Blue));
+        }
+
+        #[test]
+        fn hard_players_forget_what_a_player_lacked_once_they_play_it() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Difficulty::Hard.strategy().unwrap());
+            uno.history = vec![
+                Event::Played { player: 0, card: Card::from("blue 3") },
+                Event::Drew { player: 1, count: 1, forced: false },
+                Event::Played { player: 1, card: Card::from("blue 7") },
+                Event::Played { player: 0, card: Card::from("red 7") },
+                Event::Played { player: 1, card: Card::from("red 3") },
+                Event::Played { player: 0, card: Card::from("yellow 3") },
+            ];
+            uno.discard.push(Card::from("yellow 3"));
+            uno.players[0].hand = vec![Card::from("wild"), Card::from("red 1"), Card::from("blue 1")];
+
+            uno.automate_current_player_turn();
+
+            assert_eq!(uno.discard.last().unwrap().color, Some(Color::Red));
+        }
+
+        #[test]
+        fn expert_players_find_the_way_to_go_out() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Box::new(MctsStrategy { iterations: 300, time_limit: Duration::from_secs(5) }));
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 5"), Card::from("red skip")];
+            uno.players[1].hand = vec![Card::from("red 7")];
+
+            uno.automate_current_player_turn();
+
+            assert!(uno.game_over());
+            assert_eq!(uno.winning_player_index(), Some(0));
+        }
+
+        #[test]
+        fn duel_players_find_the_way_to_go_out() {
+            let mut uno = Uno::new(2, 0);
+            uno.set_strategy(0, Box::new(ExpectiminimaxStrategy { depth: 2, time_limit: Duration::from_secs(5) }));
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("red 5"), Card::from("red skip")];
+            uno.players[1].hand = vec![Card::from("red 7")];
+
+            uno.automate_current_player_turn();
+
+            assert!(uno.game_over());
+            assert_eq!(uno.winning_player_index(), Some(0));
+        }
+
+        #[test]
+        fn expert_players_can_play_a_whole_game() {
+            let mut uno = Uno::new(1, 2);
+            for seat in 0..3 {
+                uno.set_strategy(seat, Box::new(MctsStrategy { iterations: 10, time_limit: Duration::from_secs(5) }));
+            }
+
+            for _ in 0..1000 {
+                if uno.game_over() {
+                    break;
+                }
+                uno.automate_current_player_turn();
+            }
+
+            assert!(uno.game_over());
+        }
+
+        #[test]
+        fn expert_players_can_play_a_whole_flip_game() {
+            let mut uno = Uno::with_rules(1, 2, Rules { mode: GameMode::Flip, ..Rules::default() });
+            for seat in 0..3 {
+                uno.set_strategy(seat, Box::new(MctsStrategy { iterations: 10, time_limit: Duration::from_secs(5) }));
+            }
+
+            for _ in 0..1000 {
+                if uno.game_over() {
+                    break;
+                }
+                uno.automate_current_player_turn();
+            }
+
+            assert!(uno.game_over());
+        }
+
+        #[test]
+        fn computer_players_are_listed_in_seating_order() {
+            let uno = Uno::new(2, 2);
+
+            assert_eq!(uno.ai_seats(), vec![1, 3]);
+        }
+    }
+
+    mod deal_next {
+        use super::super::*;
+
+        #[test]
+        fn the_chosen_cards_get_drawn_first_in_order() {
+            let mut uno = Uno::new(2, 0);
+            uno.deck = ["red 1", "red 2", "red 3", "red 4"].into_iter().map(Card::from).collect();
+
+            uno.deal_next(&[2, 0]);
+
+            assert_eq!(uno.deck.pop(), Some(Card::from("red 3")));
+            assert_eq!(uno.deck.pop(), Some(Card::from("red 1")));
+        }
+    }
+
+    mod position_hash {
+        use super::super::*;
+
+        #[test]
+        fn ignores_the_order_cards_are_in() {
+            let uno = Uno::new(2, 0);
+            let mut shuffled = uno.copy_position();
+            shuffled.players[0].hand.reverse();
+            shuffled.deck.reverse();
+
+            assert_eq!(shuffled.position_hash(), uno.position_hash());
+        }
+
+        #[test]
+        fn changes_when_a_card_moves() {
+            let uno = Uno::new(2, 0);
+            let mut moved = uno.copy_position();
+            let card = moved.deck.pop().unwrap();
+            moved.players[1].hand.push(card);
+
+            assert_ne!(moved.position_hash(), uno.position_hash());
+        }
+    }
+
+    mod turn_recaps_since {
+        use super::super::*;
+
+        #[test]
+        fn gives_each_turn_its_own_recap() {
+            let mut uno = Uno::new(3, 0);
+            uno.history = vec![
+                Event::Played { player: 0, card: Card::from("red draw2") },
+                Event::Drew { player: 1, count: 2, forced: true },
+                Event::Skipped { player: 1 },
+                Event::Played { player: 2, card: Card::from("red reverse") },
+                Event::Drew { player: 0, count: 1, forced: false },
+            ];
+
+            let recaps = uno.turn_recaps_since(0);
+
+            let players: Vec<i32> = recaps.iter().map(|recap| recap.player).collect();
+            assert_eq!(players, vec![1, 3, 1]);
+            assert_eq!(recaps[0].forced_draws, vec![(2, 2)]);
+            assert_eq!(recaps[0].skipped_players, vec![2]);
+            assert!(recaps[1].reversed);
+            assert_eq!(recaps[2].drawn_cards, 1);
+        }
+
+        #[test]
+        fn forced_draws_with_no_earlier_turn_are_their_own_turn() {
+            let mut uno = Uno::new(2, 0);
+            uno.history = vec![
+                Event::Drew { player: 0, count: 4, forced: true },
+                Event::Played { player: 1, card: Card::from("red 3") },
+            ];
+
+            let recaps = uno.turn_recaps_since(0);
+
+            assert_eq!(recaps.len(), 2);
+            assert_eq!(recaps[0].player, 1);
+            assert_eq!(recaps[0].drawn_cards, 4);
+        }
+
+        #[test]
+        fn starts_from_history_start() {
+            let mut uno = Uno::new(2, 0);
+            uno.history = vec![
+                Event::Played { player: 0, card: Card::from("red 2") },
+                Event::Played { player: 1, card: Card::from("red 3") },
+            ];
+
+            let recaps = uno.turn_recaps_since(1);
+
+            assert_eq!(recaps.len(), 1);
+            assert_eq!(recaps[0].player, 2);
         }
     }
 
@@ -935,12 +3262,12 @@ mod tests {
             uno.discard.push(last_played_card);
             let valid_next_card = Card::from("red 2");
             let human_player_num_cards_before = {
-                let human_player = uno.players.iter_mut().find(|player| !player.ai).unwrap();
+                let human_player = uno.players.iter_mut().find(|player| !player.is_ai()).unwrap();
                 human_player.hand.insert(0, valid_next_card);
                 human_player.hand.len()
             };
             let ai_player_num_cards_before = {
-                let ai_player = uno.players.iter_mut().find(|player| player.ai).unwrap();
+                let ai_player = uno.players.iter_mut().find(|player| player.is_ai()).unwrap();
                 ai_player.hand.insert(0, valid_next_card);
                 ai_player.hand.len()
             };
@@ -948,11 +3275,11 @@ mod tests {
             uno.input(Input::Number(1));
 
             let human_player_num_cards_after = {
-                let human_player = uno.players.iter_mut().find(|player| !player.ai).unwrap();
+                let human_player = uno.players.iter_mut().find(|player| !player.is_ai()).unwrap();
                 human_player.hand.len()
             };
             let ai_player_num_cards_after = {
-                let ai_player = uno.players.iter_mut().find(|player| player.ai).unwrap();
+                let ai_player = uno.players.iter_mut().find(|player| player.is_ai()).unwrap();
                 ai_player.hand.len()
             };
 
@@ -970,16 +3297,16 @@ mod tests {
             uno.discard.push(last_played_card);
             let valid_next_card = Card::from("red 2");
             {
-                let human_player = uno.players.iter_mut().find(|player| !player.ai).unwrap();
+                let human_player = uno.players.iter_mut().find(|player| !player.is_ai()).unwrap();
                 human_player.hand.insert(0, valid_next_card);
             };
             {
-                let ai_player = uno.players.iter_mut().find(|player| player.ai).unwrap();
+                let ai_player = uno.players.iter_mut().find(|player| player.is_ai()).unwrap();
                 ai_player.hand.insert(0, valid_next_card);
             };
 
             let human_player_num_cards_before = {
-                let human_player = uno.players.iter_mut().find(|player| !player.ai).unwrap();
+                let human_player = uno.players.iter_mut().find(|player| !player.is_ai()).unwrap();
                 human_player.hand.len()
             };
 
@@ -987,11 +3314,114 @@ mod tests {
             uno.input(Input::Number(1));
 
             let human_player_num_cards_after = {
-                let human_player = uno.players.iter_mut().find(|player| !player.ai).unwrap();
+                let human_player = uno.players.iter_mut().find(|player| !player.is_ai()).unwrap();
                 human_player.hand.len()
             };
 
             assert_eq!(human_player_num_cards_before - 1, human_player_num_cards_after);
         }
+
+        #[test]
+        fn recaps_every_turn_since_the_humans_last_move() {
+            let mut uno = Uno::new(1,2);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand.insert(0, Card::from("red 2"));
+            uno.players[1].hand = vec![Card::from("red 3"), Card::from("blue 4"), Card::from("green 5")];
+            uno.players[2].hand = vec![Card::from("red 4"), Card::from("blue 5"), Card::from("green 6")];
+
+            uno.input(Input::Number(1));
+
+            let players: Vec<i32> = uno.ui.turn_recaps.iter().map(|recap| recap.player).collect();
+            assert_eq!(players, vec![1, 2, 3]);
+        }
+
+        #[test]
+        fn explains_the_moves_computer_players_made() {
+            let mut uno = Uno::new(1,1);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand.insert(0, Card::from("red 2"));
+            uno.players[1].hand = vec![Card::from("red 3"), Card::from("blue 4"), Card::from("green 5")];
+
+            uno.input(Input::Number(1));
+            uno.input(Input::Text("e".to_string()));
+
+            assert_eq!(uno.ui.explanations.len(), 1);
+            let (player, explanation) = &uno.ui.explanations[0];
+            assert_eq!(*player, 2);
+            assert_eq!(explanation.action, "play a red 3");
+            assert!(uno.ui.error.is_none());
+        }
+
+        #[test]
+        fn explanations_are_only_shown_until_the_next_move() {
+            let mut uno = Uno::new(1,1);
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand.insert(0, Card::from("red 2"));
+
+            uno.input(Input::Text("e".to_string()));
+            assert!(uno.ui.error.is_some());
+
+            uno.input(Input::Number(1));
+            uno.input(Input::Text("e".to_string()));
+            assert!(!uno.ui.explanations.is_empty());
+
+            uno.input(Input::Text("d".to_string()));
+            assert!(uno.ui.explanations.is_empty());
+        }
+
+        #[test]
+        fn paced_computer_turns_are_shown_one_at_a_time() {
+            let mut uno = Uno::new(1,2);
+            uno.set_pace(Pace::Delay(std::time::Duration::ZERO));
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand.insert(0, Card::from("red 2"));
+            uno.players[1].hand.insert(0, Card::from("red 3"));
+            uno.players[2].hand.insert(0, Card::from("red 4"));
+
+            uno.input(Input::Number(1));
+
+            assert_eq!(uno.current_player(), 0);
+            assert_eq!(uno.players[1].hand.len(), 7);
+            assert_eq!(uno.players[2].hand.len(), 7);
+            // Each turn was shown as it happened, and the human still gets caught up on all of them.
+            let players: Vec<i32> = uno.ui.turn_recaps.iter().map(|recap| recap.player).collect();
+            assert_eq!(players, vec![1, 2, 3]);
+            assert!(!uno.ui.watching);
+        }
+
+        #[test]
+        fn hints_point_out_the_card_to_play() {
+            let mut uno = Uno::new(1,1);
+            uno.set_hint_strategy(Some(Box::new(BasicStrategy)));
+            uno.discard.push(Card::from("red 1"));
+            uno.players[0].hand = vec![Card::from("blue 2"), Card::from("red 2"), Card::from("green 3")];
+
+            uno.input(Input::Text("h".to_string()));
+
+            let (card_index, explanation) = uno.ui.hint.as_ref().unwrap();
+            assert_eq!(*card_index, Some(1));
+            assert_eq!(explanation.action, "play a red 2");
+            assert_eq!(uno.players[0].hand.len(), 3);
+        }
+
+        #[test]
+        fn hints_can_be_turned_off() {
+            let mut uno = Uno::new(1,1);
+            uno.set_hint_strategy(None);
+
+            uno.input(Input::Text("h".to_string()));
+
+            assert!(uno.ui.hint.is_none());
+            assert!(uno.ui.error.is_some());
+        }
+
+        #[test]
+        fn games_without_humans_dont_keep_explanations() {
+            let mut uno = Uno::new(0,2);
+
+            uno.automate_current_player_turn();
+
+            assert!(uno.explanations.is_empty());
+        }
     }
 }
@@ -0,0 +1,16 @@
+//! Uno against computer players, along with what's needed to simulate games, tune and rate
+//! computer players, and train new ones. The `rust-uno` binary is the command line front end.
+
+pub mod ai;
+pub mod card;
+pub mod config;
+pub mod endgame;
+pub mod env;
+pub mod game;
+pub mod profile;
+pub mod rating;
+pub mod simulate;
+pub mod tune;
+pub mod user_input;
+pub mod ui;
+pub mod view;
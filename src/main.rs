@@ -1,13 +1,210 @@
-mod card;
-mod game;
-mod user_input;
-mod ui;
+use std::{io, num::NonZeroUsize, thread};
 
-use game::Uno;
-use user_input::get_user_input;
+use rand::thread_rng;
+
+use rust_uno::{
+    ai,
+    config::Config,
+    game::Uno,
+    profile::Profiles,
+    rating::Ratings,
+    simulate,
+    tune,
+    user_input::get_user_input,
+};
+
+/// Options that take a value, named the same as the settings in config files.
+const SETTINGS: [&str; 16] = [
+    "mode", "custom-wild", "teams", "humans", "ai", "names", "difficulty", "expert-iterations", "expert-time", "duel-depth",
+    "duel-time", "bot", "bot-timeout", "weights", "hint-difficulty", "pace",
+];
+/// Where ratings are kept unless `--ratings` says otherwise.
+const DEFAULT_RATINGS_PATH: &str = "ratings.txt";
+/// Where how humans tend to play is kept unless `--profiles` says otherwise.
+const DEFAULT_PROFILES_PATH: &str = "profiles.txt";
+/// Options that are turned on just by being there.
+const FLAGS: [&str; 3] = ["signals", "last-player-standing", "ranked"];
 
 fn main() {
-    let mut uno = Uno::new(1, 1);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("echo-bot") {
+        if let Err(error) = ai::echo_bot(io::stdin().lock(), io::stdout().lock()) {
+            eprintln!("{}", error);
+        }
+        return;
+    }
+    let value_of = |flag: &str| -> Option<&str> {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|flag_index| args.get(flag_index + 1))
+            .map(String::as_str)
+    };
+
+    // Options on the command line win over the ones in a loaded config file.
+    let mut config = match value_of("--config") {
+        Some(path) => match Config::load(path) {
+            Ok(config) => config,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        },
+        None => Config::default(),
+    };
+    for setting in SETTINGS {
+        let flag = format!("--{}", setting);
+        if !args.contains(&flag) {
+            continue;
+        }
+        let result = match value_of(&flag) {
+            Some(value) => config.set(setting, value),
+            None => Err(format!("{} needs a value.", flag)),
+        };
+        if let Err(error) = result {
+            eprintln!("{}", error);
+            return;
+        }
+    }
+    for flag in FLAGS {
+        if args.contains(&format!("--{}", flag)) {
+            config.set(flag, "true").unwrap();
+        }
+    }
+    if let Some(path) = value_of("--save-config") {
+        if let Err(error) = config.save(path) {
+            eprintln!("{}", error);
+            return;
+        }
+    }
+
+    let ratings_path = value_of("--ratings").unwrap_or(DEFAULT_RATINGS_PATH);
+    let mut ratings = match Ratings::load(ratings_path) {
+        Ok(ratings) => ratings,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+    let command = args.first().map(String::as_str);
+
+    if command == Some("leaderboard") {
+        for (place, (name, rating)) in ratings.leaderboard().into_iter().enumerate() {
+            println!(
+                "{}) {}: {:.1} ({:.1} ± {:.1} over {} games)",
+                place + 1,
+                name,
+                rating.conservative(),
+                rating.mean,
+                3.0 * rating.deviation,
+                rating.games,
+            );
+        }
+        return;
+    }
+
+    // `rust-uno simulate` plays computer players against each other instead of starting a game.
+    if command == Some("simulate") {
+        let default_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let games = number_option(value_of("--games"), "--games", 1000);
+        let threads = number_option(value_of("--threads"), "--threads", default_threads);
+        match (games, threads) {
+            (Ok(_), Ok(_)) if config.ais < 2 => eprintln!("Simulations need at least 2 computer players."),
+            (Ok(games), Ok(threads)) => match simulate::simulate(&config, games, threads, &mut ratings) {
+                Ok(results) => {
+                    print!("{}", results);
+                    save_ratings(&ratings, ratings_path);
+                }
+                Err(error) => eprintln!("{}", error),
+            },
+            (Err(error), _) | (_, Err(error)) => eprintln!("{}", error),
+        }
+        return;
+    }
+    // `rust-uno tune` tunes the weights tuned computer players use by having them play themselves.
+    if command == Some("tune") {
+        let rounds = number_option(value_of("--rounds"), "--rounds", 20);
+        let deals = number_option(value_of("--deals"), "--deals", 100);
+        let (rounds, deals) = match (rounds, deals) {
+            (Ok(rounds), Ok(deals)) => (rounds, deals),
+            (Err(error), _) | (_, Err(error)) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+        if config.ais < 2 {
+            eprintln!("Tuning needs at least 2 computer players.");
+            return;
+        }
+        if let Err(error) = config.rules.check_teams(config.ais) {
+            eprintln!("{}", error);
+            return;
+        }
+        let weights = match ai::Weights::load(&config.weights) {
+            Ok(weights) => weights,
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        };
+        let players = config.ais as usize;
+        tune::tune(config.rules, players, weights, rounds, deals, &mut thread_rng(), |round, weights| {
+            println!("Round {}: the nudged weights won {:.1}% of games.", round.number, round.win_rate * 100.0);
+            // Saving as soon as there's something better means stopping early doesn't lose it.
+            if round.improved {
+                if let Err(error) = weights.save(&config.weights) {
+                    eprintln!("{}", error);
+                }
+            }
+        });
+        println!("The best weights are in {}.", config.weights);
+        return;
+    }
+    if config.humans < 1 {
+        eprintln!("Games need at least 1 human player. Use \"rust-uno simulate\" to watch computer players.");
+        return;
+    }
+    if config.humans + config.ais < 2 {
+        eprintln!("Games need at least 2 players.");
+        return;
+    }
+    if let Err(error) = config.rules.check_teams(config.humans + config.ais) {
+        eprintln!("{}", error);
+        return;
+    }
+
+    let profiles_path = value_of("--profiles").unwrap_or(DEFAULT_PROFILES_PATH);
+    let mut profiles = match Profiles::load(profiles_path) {
+        Ok(profiles) => profiles,
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    };
+
+    let mut uno = Uno::with_rules(config.humans, config.ais, config.rules);
+    // Only humans get profiles, since computer players play the same way every game.
+    let ai_seats = uno.ai_seats();
+    let human_names: Vec<Option<String>> = config.rated_names(&ai_seats).into_iter().enumerate()
+        .map(|(seat, name)| name.filter(|_| !ai_seats.contains(&seat)))
+        .collect();
+    uno.set_past_profiles(profiles.table(&human_names));
+    for (ai_number, seat) in uno.ai_seats().into_iter().enumerate() {
+        match config.strategy(ai_number) {
+            Ok(strategy) => uno.set_strategy(seat, strategy),
+            Err(error) => {
+                eprintln!("{}", error);
+                return;
+            }
+        }
+    }
+    uno.set_pace(config.pace);
+    match config.hint_strategy() {
+        Ok(hint_strategy) => uno.set_hint_strategy(hint_strategy),
+        Err(error) => {
+            eprintln!("{}", error);
+            return;
+        }
+    }
     loop {
         uno.render();
 
@@ -20,4 +217,26 @@ fn main() {
             break;
         }
     }
+
+    let finishing_order: Vec<usize> = uno.finishing_order().into_iter().map(|seat| seat as usize).collect();
+    ratings.record_game(&finishing_order, &config.rated_names(&uno.ai_seats()), &uno.view(0).teams);
+    save_ratings(&ratings, ratings_path);
+    profiles.record_game(&human_names, uno.profiles());
+    if let Err(error) = profiles.save(profiles_path) {
+        eprintln!("{}", error);
+    }
+}
+
+fn save_ratings(ratings: &Ratings, path: &str) {
+    if let Err(error) = ratings.save(path) {
+        eprintln!("{}", error);
+    }
+}
+
+/// Reads a number given for `flag`, or `default` if it wasn't given.
+fn number_option(value: Option<&str>, flag: &str, default: usize) -> Result<usize, String> {
+    match value {
+        Some(value) => value.parse().map_err(|_| format!("{} needs a number, not \"{}\".", flag, value)),
+        None => Ok(default),
+    }
 }
@@ -0,0 +1,241 @@
+//! This mod is for remembering how each human tends to play from game to game, so computer
+//! players can take advantage of it.
+
+use std::{collections::BTreeMap, fmt::Display, fs, io::ErrorKind, str::FromStr};
+
+use serde::Serialize;
+
+use crate::card::Color;
+
+/// Every color, in the order profiles keep them.
+const COLORS: [Color; 8] = [
+    Color::Red, Color::Blue, Color::Green, Color::Yellow, Color::Pink, Color::Teal, Color::Orange, Color::Purple,
+];
+/// How many made up times a guess counts for before there's anything to go on. The more real
+/// times there are, the less the guess matters.
+const GUESS_WEIGHT: f64 = 5.0;
+
+/// How many times something happened out of how many times it could have.
+#[derive(Copy, Clone, Default, PartialEq, Debug, Serialize)]
+pub struct Tally {
+    pub times: u32,
+    pub out_of: u32,
+}
+
+impl Tally {
+    pub fn record(&mut self, happened: bool) {
+        self.times += happened as u32;
+        self.out_of += 1;
+    }
+
+    /// How often it happens, starting from `guess` when there isn't much to go on.
+    pub fn rate(&self, guess: f64) -> f64 {
+        (self.times as f64 + guess * GUESS_WEIGHT) / (self.out_of as f64 + GUESS_WEIGHT)
+    }
+
+    fn add(&mut self, other: Tally) {
+        self.times += other.times;
+        self.out_of += other.out_of;
+    }
+}
+
+/// How a player tends to play.
+#[derive(Clone, Default, PartialEq, Debug, Serialize)]
+pub struct Profile {
+    /// How many of their moves they made with a wild in hand.
+    pub holding_wilds: Tally,
+    /// How often they call Uno when they get down to one card.
+    pub uno_calls: Tally,
+    /// For each color, how many of their moves on it were drawing instead of playing.
+    draws_on: [Tally; COLORS.len()],
+}
+
+impl Profile {
+    pub fn draws_on(&self, color: Color) -> Tally {
+        self.draws_on[color_index(color)]
+    }
+
+    pub fn draws_on_mut(&mut self, color: Color) -> &mut Tally {
+        &mut self.draws_on[color_index(color)]
+    }
+
+    /// How much more often than usual they draw when `color` is on top. Above zero means they're
+    /// often short on it.
+    pub fn short_on(&self, color: Color) -> f64 {
+        let mut all_draws = Tally::default();
+        for draws in self.draws_on {
+            all_draws.add(draws);
+        }
+        let usual = all_draws.rate(0.3);
+        self.draws_on(color).rate(usual) - usual
+    }
+
+    fn add(&mut self, other: &Profile) {
+        self.holding_wilds.add(other.holding_wilds);
+        self.uno_calls.add(other.uno_calls);
+        for (draws, other_draws) in self.draws_on.iter_mut().zip(other.draws_on) {
+            draws.add(other_draws);
+        }
+    }
+}
+
+fn color_index(color: Color) -> usize {
+    COLORS.iter().position(|other| *other == color).unwrap()
+}
+
+/// Profiles for every named human who's played, by name. Saved to files as
+/// `name = holding-wilds 3/10 uno-calls 4/5 red 2/9 ...` lines, where each color is how often they
+/// drew on it.
+#[derive(Default, PartialEq, Debug)]
+pub struct Profiles {
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profiles {
+    /// Loads profiles from `path`. Nobody's been seen playing before the file's been made, so a
+    /// missing file is the same as an empty one.
+    pub fn load(path: &str) -> Result<Profiles, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse(),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Profiles::default()),
+            Err(error) => Err(format!("Couldn't read the profiles file {}: {}", path, error)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|error| format!("Couldn't save the profiles file {}: {}", path, error))
+    }
+
+    /// Everyone's profile going into a game, by seat. `names` is who's in each seat, and seats
+    /// without a name get an empty profile.
+    pub fn table(&self, names: &[Option<String>]) -> Vec<Profile> {
+        names.iter()
+            .map(|name| name.as_ref().and_then(|name| self.profiles.get(name)).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    /// Adds what was seen in a game to everyone's profile. `names` is who's in each seat and
+    /// `profiles` is how they played this game, both by seat.
+    pub fn record_game(&mut self, names: &[Option<String>], profiles: &[Profile]) {
+        for (name, profile) in names.iter().zip(profiles) {
+            if let Some(name) = name {
+                self.profiles.entry(name.clone()).or_default().add(profile);
+            }
+        }
+    }
+}
+
+impl FromStr for Profiles {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut profiles = Profiles::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let bad_line = || format!("\"{}\" should look like \"name = holding-wilds 3/10 uno-calls 4/5 red 2/9 ...\".", line);
+            let (name, tallies) = line.split_once('=').ok_or_else(bad_line)?;
+            let mut profile = Profile::default();
+            let words: Vec<&str> = tallies.split_whitespace().collect();
+            for pair in words.chunks(2) {
+                let [key, tally] = pair else {
+                    return Err(bad_line());
+                };
+                let (times, out_of) = tally.split_once('/').ok_or_else(bad_line)?;
+                let tally = Tally {
+                    times: times.parse().map_err(|_| bad_line())?,
+                    out_of: out_of.parse().map_err(|_| bad_line())?,
+                };
+                match *key {
+                    "holding-wilds" => profile.holding_wilds = tally,
+                    "uno-calls" => profile.uno_calls = tally,
+                    color => match COLORS.iter().find(|known| known.to_string() == color) {
+                        Some(color) => *profile.draws_on_mut(*color) = tally,
+                        None => return Err(bad_line()),
+                    },
+                }
+            }
+            profiles.profiles.insert(name.trim().to_string(), profile);
+        }
+        Ok(profiles)
+    }
+}
+
+impl Display for Profiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, profile) in &self.profiles {
+            let tally = |tally: Tally| format!("{}/{}", tally.times, tally.out_of);
+            write!(f, "{} = holding-wilds {} uno-calls {}", name, tally(profile.holding_wilds), tally(profile.uno_calls))?;
+            for color in COLORS {
+                write!(f, " {} {}", color, tally(profile.draws_on(color)))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod from_str {
+        use super::super::*;
+
+        #[test]
+        fn reads_back_what_it_writes() {
+            let mut profile = Profile { holding_wilds: Tally { times: 3, out_of: 10 }, ..Profile::default() };
+            profile.draws_on_mut(Color::Blue).record(true);
+            let mut profiles = Profiles::default();
+            profiles.record_game(&[Some("alice".to_string())], &[profile]);
+
+            let read_back: Profiles = profiles.to_string().parse().unwrap();
+
+            assert_eq!(read_back, profiles);
+        }
+
+        #[test]
+        fn rejects_lines_without_tallies() {
+            assert!("alice = holding-wilds 3".parse::<Profiles>().is_err());
+            assert!("alice = luck 1/2".parse::<Profiles>().is_err());
+            assert!("alice".parse::<Profiles>().is_err());
+        }
+    }
+
+    mod record_game {
+        use super::super::*;
+
+        #[test]
+        fn adds_to_what_was_seen_before() {
+            let mut profile = Profile::default();
+            profile.uno_calls.record(false);
+            let mut profiles = Profiles::default();
+            let names = [Some("alice".to_string()), None];
+
+            profiles.record_game(&names, &[profile.clone(), profile.clone()]);
+            profiles.record_game(&names, &[profile.clone(), profile]);
+
+            let table = profiles.table(&names);
+            assert_eq!(table[0].uno_calls, Tally { times: 0, out_of: 2 });
+            assert_eq!(table[1], Profile::default());
+        }
+    }
+
+    mod short_on {
+        use super::super::*;
+
+        #[test]
+        fn colors_drawn_on_more_than_usual_stand_out() {
+            let mut profile = Profile::default();
+            for _ in 0..10 {
+                profile.draws_on_mut(Color::Red).record(false);
+                profile.draws_on_mut(Color::Blue).record(true);
+            }
+
+            assert!(profile.short_on(Color::Blue) > 0.0);
+            assert!(profile.short_on(Color::Red) < 0.0);
+        }
+
+        #[test]
+        fn nobody_is_short_on_anything_before_theyve_been_seen() {
+            assert!(Profile::default().short_on(Color::Green).abs() < 1e-9);
+        }
+    }
+}
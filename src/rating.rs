@@ -0,0 +1,285 @@
+//! This mod is for keeping track of how strong players and computer players are across games.
+
+use std::{collections::BTreeMap, fmt::Display, fs, io::ErrorKind, str::FromStr};
+
+/// Where everyone starts out, and how unsure that is.
+const STARTING_MEAN: f64 = 25.0;
+const STARTING_DEVIATION: f64 = STARTING_MEAN / 3.0;
+/// How much luck there is in a single game, as a rating difference.
+const LUCK: f64 = STARTING_DEVIATION / 2.0;
+/// Keeps uncertainty from ever shrinking to nothing.
+const MIN_VARIANCE_FACTOR: f64 = 0.0001;
+
+/// How strong someone seems to be. Their real strength is probably within a few `deviation`s of
+/// `mean`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Rating {
+    pub mean: f64,
+    pub deviation: f64,
+    pub games: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Rating { mean: STARTING_MEAN, deviation: STARTING_DEVIATION, games: 0 }
+    }
+}
+
+impl Rating {
+    /// A rating they're very likely to be at least as strong as, which is what the leaderboard
+    /// goes by so players with only a few lucky games don't come out on top.
+    pub fn conservative(&self) -> f64 {
+        self.mean - 3.0 * self.deviation
+    }
+}
+
+/// Ratings for every human profile and computer difficulty that's played, by name. Saved to
+/// files as `name = mean deviation games` lines.
+#[derive(Default, PartialEq, Debug)]
+pub struct Ratings {
+    ratings: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    /// Loads ratings from `path`. Nobody's rated before the file's been made, so a missing file
+    /// is the same as an empty one.
+    pub fn load(path: &str) -> Result<Ratings, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse(),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(Ratings::default()),
+            Err(error) => Err(format!("Couldn't read the ratings file {}: {}", path, error)),
+        }
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|error| format!("Couldn't save the ratings file {}: {}", path, error))
+    }
+
+    pub fn rating(&self, name: &str) -> Rating {
+        self.ratings.get(name).copied().unwrap_or_default()
+    }
+
+    /// Updates ratings after a game. `finishing_order` is every seat from first place to last,
+    /// `names` is who was rated in each seat, and `teams` is each seat's team. Players without a
+    /// name aren't rated, and teams place wherever their best player did. Seats rated under the
+    /// same name, like two computer players of the same difficulty, count once at their best place.
+    pub fn record_game(&mut self, finishing_order: &[usize], names: &[Option<String>], teams: &[Option<i32>]) {
+        // Teams of names in the order they placed.
+        let mut placings: Vec<(Option<i32>, Vec<&str>)> = Vec::new();
+        for seat in finishing_order {
+            let Some(name) = &names[*seat] else {
+                continue;
+            };
+            if placings.iter().any(|(_, members)| members.contains(&name.as_str())) {
+                continue;
+            }
+            match placings.iter_mut().find(|(team, _)| teams[*seat].is_some() && *team == teams[*seat]) {
+                Some((_, members)) => members.push(name),
+                None => placings.push((teams[*seat], vec![name])),
+            }
+        }
+        if placings.len() < 2 {
+            return;
+        }
+
+        // Everyone's update is worked out from the ratings going into the game, then applied.
+        let team_ratings: Vec<Vec<Rating>> = placings.iter()
+            .map(|(_, members)| members.iter().map(|name| self.rating(name)).collect())
+            .collect();
+        let updates = weng_lin_updates(&team_ratings);
+        for ((_, members), team_updates) in placings.iter().zip(updates) {
+            for (name, (mean_change, variance_factor)) in members.iter().zip(team_updates) {
+                let rating = self.ratings.entry(name.to_string()).or_default();
+                rating.mean += mean_change;
+                rating.deviation *= variance_factor.sqrt();
+                rating.games += 1;
+            }
+        }
+    }
+
+    /// Everyone who's been rated, from the strongest to the weakest.
+    pub fn leaderboard(&self) -> Vec<(&str, Rating)> {
+        let mut leaderboard: Vec<(&str, Rating)> = self.ratings.iter().map(|(name, rating)| (name.as_str(), *rating)).collect();
+        leaderboard.sort_by(|(_, rating), (_, other_rating)| other_rating.conservative().total_cmp(&rating.conservative()));
+        leaderboard
+    }
+}
+
+/// Weng and Lin's Bradley-Terry rating update, where every team is compared against every other
+/// team. `teams` are in the order they placed, best first. Gives back how much each player's mean
+/// changes and what their variance gets multiplied by.
+fn weng_lin_updates(teams: &[Vec<Rating>]) -> Vec<Vec<(f64, f64)>> {
+    let team_means: Vec<f64> = teams.iter().map(|team| team.iter().map(|rating| rating.mean).sum()).collect();
+    let team_variances: Vec<f64> = teams.iter().map(|team| team.iter().map(|rating| rating.deviation.powi(2)).sum()).collect();
+
+    teams.iter().enumerate()
+        .map(|(team, ratings)| {
+            let mut mean_change = 0.0;
+            let mut variance_change = 0.0;
+            for other_team in (0..teams.len()).filter(|other_team| *other_team != team) {
+                let c = (team_variances[team] + team_variances[other_team] + 2.0 * LUCK.powi(2)).sqrt();
+                let chance_to_beat = 1.0 / (1.0 + ((team_means[other_team] - team_means[team]) / c).exp());
+                let score = if team < other_team { 1.0 } else { 0.0 };
+                mean_change += team_variances[team] / c * (score - chance_to_beat);
+                let gamma = team_variances[team].sqrt() / c;
+                variance_change += gamma * team_variances[team] / c.powi(2) * chance_to_beat * (1.0 - chance_to_beat);
+            }
+            // Players whose ratings are less certain take more of the change.
+            ratings.iter()
+                .map(|rating| {
+                    let share = rating.deviation.powi(2) / team_variances[team];
+                    (share * mean_change, (1.0 - share * variance_change).max(MIN_VARIANCE_FACTOR))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl FromStr for Ratings {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut ratings = Ratings::default();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let bad_line = || format!("\"{}\" should look like \"name = mean deviation games\".", line);
+            let (name, numbers) = line.split_once('=').ok_or_else(bad_line)?;
+            let numbers: Vec<&str> = numbers.split_whitespace().collect();
+            let [mean, deviation, games] = numbers[..] else {
+                return Err(bad_line());
+            };
+            let rating = Rating {
+                mean: mean.parse().map_err(|_| bad_line())?,
+                deviation: deviation.parse().map_err(|_| bad_line())?,
+                games: games.parse().map_err(|_| bad_line())?,
+            };
+            ratings.ratings.insert(name.trim().to_string(), rating);
+        }
+        Ok(ratings)
+    }
+}
+
+impl Display for Ratings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, rating) in &self.ratings {
+            writeln!(f, "{} = {} {} {}", name, rating.mean, rating.deviation, rating.games)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod record_game {
+        use super::super::*;
+
+        fn names(names: &[&str]) -> Vec<Option<String>> {
+            names.iter().map(|name| Some(name.to_string())).collect()
+        }
+
+        #[test]
+        fn winners_go_up_and_losers_go_down() {
+            let mut ratings = Ratings::default();
+
+            ratings.record_game(&[1, 0, 2], &names(&["easy", "hard", "medium"]), &[None; 3]);
+
+            assert!(ratings.rating("hard").mean > STARTING_MEAN);
+            assert!(ratings.rating("easy").mean < ratings.rating("hard").mean);
+            assert!(ratings.rating("medium").mean < ratings.rating("easy").mean);
+            assert!(ratings.rating("hard").deviation < STARTING_DEVIATION);
+            assert_eq!(ratings.rating("hard").games, 1);
+        }
+
+        #[test]
+        fn beating_a_stronger_player_counts_for_more() {
+            let mut ratings = Ratings::default();
+            ratings.ratings.insert("strong".to_string(), Rating { mean: 35.0, deviation: 3.0, games: 50 });
+            ratings.ratings.insert("weak".to_string(), Rating { mean: 15.0, deviation: 3.0, games: 50 });
+            ratings.ratings.insert("middling".to_string(), Rating { mean: 25.0, deviation: 3.0, games: 50 });
+
+            let mut upset = Ratings { ratings: ratings.ratings.clone() };
+            upset.record_game(&[0, 1], &names(&["middling", "strong"]), &[None; 2]);
+            let mut expected = Ratings { ratings: ratings.ratings.clone() };
+            expected.record_game(&[0, 1], &names(&["middling", "weak"]), &[None; 2]);
+
+            assert!(upset.rating("middling").mean > expected.rating("middling").mean);
+        }
+
+        #[test]
+        fn players_with_the_same_name_count_once_at_their_best_place() {
+            let mut ratings = Ratings::default();
+
+            ratings.record_game(&[0, 1, 2], &names(&["medium", "easy", "medium"]), &[None; 3]);
+
+            let mut expected = Ratings::default();
+            expected.record_game(&[0, 1], &names(&["medium", "easy"]), &[None; 2]);
+            assert_eq!(ratings.rating("medium"), expected.rating("medium"));
+            assert_eq!(ratings.rating("easy"), expected.rating("easy"));
+            assert_eq!(ratings.rating("medium").games, 1);
+        }
+
+        #[test]
+        fn a_game_against_only_yourself_changes_nothing() {
+            let mut ratings = Ratings::default();
+
+            ratings.record_game(&[0, 1, 2], &names(&["medium", "medium", "medium"]), &[None; 3]);
+
+            assert_eq!(ratings.rating("medium"), Rating::default());
+            assert!(ratings.leaderboard().is_empty());
+        }
+
+        #[test]
+        fn teammates_place_together() {
+            let mut ratings = Ratings::default();
+
+            ratings.record_game(&[0, 1, 2, 3], &names(&["alice", "bob", "carol", "dave"]), &[Some(0), Some(1), Some(0), Some(1)]);
+
+            assert!(ratings.rating("carol").mean > STARTING_MEAN);
+            assert!(ratings.rating("bob").mean < STARTING_MEAN);
+        }
+
+        #[test]
+        fn unnamed_players_arent_rated() {
+            let mut ratings = Ratings::default();
+
+            ratings.record_game(&[0, 1], &[None, Some("medium".to_string())], &[None; 2]);
+
+            assert_eq!(ratings, Ratings::default());
+        }
+    }
+
+    mod from_str {
+        use super::super::*;
+
+        #[test]
+        fn reads_back_what_it_writes() {
+            let mut ratings = Ratings::default();
+            ratings.record_game(&[0, 1], &[Some("alice".to_string()), Some("expert".to_string())], &[None; 2]);
+
+            let read_back: Ratings = ratings.to_string().parse().unwrap();
+
+            assert_eq!(read_back, ratings);
+        }
+
+        #[test]
+        fn rejects_lines_without_a_rating() {
+            assert!("medium = 25".parse::<Ratings>().is_err());
+            assert!("medium".parse::<Ratings>().is_err());
+        }
+    }
+
+    mod leaderboard {
+        use super::super::*;
+
+        #[test]
+        fn goes_by_how_strong_players_surely_are() {
+            let mut ratings = Ratings::default();
+            ratings.ratings.insert("lucky".to_string(), Rating { mean: 30.0, deviation: 8.0, games: 1 });
+            ratings.ratings.insert("steady".to_string(), Rating { mean: 28.0, deviation: 1.0, games: 500 });
+
+            let names: Vec<&str> = ratings.leaderboard().into_iter().map(|(name, _)| name).collect();
+
+            assert_eq!(names, vec!["steady", "lucky"]);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! This mod is for playing computer players against each other with nobody at the keyboard, to
+//! see how well they do.
+
+use std::{fmt::Display, thread};
+
+use crate::{ai::Difficulty, config::Config, game::Uno, rating::Ratings};
+
+/// Games still going after this many turns are given up on, so a strategy that never gets
+/// anywhere can't hold up the rest.
+pub const MAX_TURNS: usize = 10_000;
+
+/// How a batch of computer only games went. The lineup of computer players moves a seat over each
+/// game, so everyone gets a turn in every seat and going first doesn't favor anyone's results.
+#[derive(Debug, PartialEq)]
+pub struct SimulationResults {
+    /// How well each computer player plays, in the order the config lists them.
+    pub lineup: Vec<Difficulty>,
+    pub games: usize,
+    /// Games nobody had won after `MAX_TURNS` turns.
+    pub unfinished: usize,
+    /// Games won by each computer player in the lineup.
+    pub lineup_wins: Vec<usize>,
+    /// Games won from each seat, whoever was sitting there.
+    pub seat_wins: Vec<usize>,
+    /// Turns taken across every game.
+    pub turns: usize,
+}
+
+/// How a single game went.
+struct GameResult {
+    /// How many seats over the lineup was moved.
+    rotation: usize,
+    winning_seat: Option<usize>,
+    finishing_order: Vec<usize>,
+    teams: Vec<Option<i32>>,
+    turns: usize,
+}
+
+/// Plays `games` games between the computer players set up in `config`, split across `threads`
+/// threads, and rates each computer player on how they did. Human players in the config are left
+/// out. Fails if the computer players can't be split into teams or a bot can't be started.
+pub fn simulate(config: &Config, games: usize, threads: usize, ratings: &mut Ratings) -> Result<SimulationResults, String> {
+    config.rules.check_teams(config.ais)?;
+    let players = config.ais as usize;
+    let threads = threads.clamp(1, games.max(1));
+    let mut results = SimulationResults {
+        lineup: (0..players).map(|ai_number| config.difficulty(ai_number)).collect(),
+        games: 0,
+        unfinished: 0,
+        lineup_wins: vec![0; players],
+        seat_wins: vec![0; players],
+        turns: 0,
+    };
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|thread| scope.spawn(move || {
+                (thread..games).step_by(threads)
+                    .map(|game| play_game(config, game % players))
+                    .collect::<Result<Vec<GameResult>, String>>()
+            }))
+            .collect();
+        for handle in handles {
+            for game in handle.join().expect("simulated games shouldn't panic")? {
+                if game.winning_seat.is_some() {
+                    let names: Vec<Option<String>> = (0..players)
+                        .map(|seat| Some(config.rated_name((seat + game.rotation) % players)))
+                        .collect();
+                    ratings.record_game(&game.finishing_order, &names, &game.teams);
+                }
+                results.add(game);
+            }
+        }
+        Ok(results)
+    })
+}
+
+/// Plays one game to the end with the lineup moved `rotation` seats over.
+fn play_game(config: &Config, rotation: usize) -> Result<GameResult, String> {
+    let players = config.ais as usize;
+    let mut uno = Uno::with_rules(0, config.ais, config.rules);
+    for seat in 0..players {
+        uno.set_strategy(seat, config.strategy((seat + rotation) % players)?);
+    }
+    let mut turns = 0;
+    while !uno.game_over() && turns < MAX_TURNS {
+        uno.automate_current_player_turn();
+        turns += 1;
+    }
+    Ok(GameResult {
+        rotation,
+        winning_seat: uno.winning_player_index().map(|seat| seat as usize),
+        finishing_order: uno.finishing_order().into_iter().map(|seat| seat as usize).collect(),
+        teams: uno.view(0).teams,
+        turns,
+    })
+}
+
+impl SimulationResults {
+    fn add(&mut self, game: GameResult) {
+        self.games += 1;
+        self.turns += game.turns;
+        match game.winning_seat {
+            Some(seat) => {
+                self.seat_wins[seat] += 1;
+                self.lineup_wins[(seat + game.rotation) % self.lineup.len()] += 1;
+            }
+            None => self.unfinished += 1,
+        }
+    }
+
+    /// The share of games won out of `wins`, along with how far off that could be by chance.
+    fn win_rate(&self, wins: usize) -> (f64, f64) {
+        let win_rate = wins as f64 / self.games as f64;
+        (win_rate, confidence_interval(win_rate, self.games))
+    }
+}
+
+/// Half the width of the 95% confidence interval around a win rate out of `games` games, going
+/// by the normal approximation.
+pub fn confidence_interval(win_rate: f64, games: usize) -> f64 {
+    1.96 * (win_rate * (1.0 - win_rate) / games as f64).sqrt()
+}
+
+impl Display for SimulationResults {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.games == 0 {
+            return writeln!(f, "No games were played.");
+        }
+        writeln!(f, "Played {} games, {:.1} turns long on average.", self.games, self.turns as f64 / self.games as f64)?;
+        if self.unfinished > 0 {
+            writeln!(f, "{} games were given up on after {} turns.", self.unfinished, MAX_TURNS)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Wins by computer player:")?;
+        for (ai_number, difficulty) in self.lineup.iter().enumerate() {
+            let (win_rate, interval) = self.win_rate(self.lineup_wins[ai_number]);
+            writeln!(f, "  AI {} ({}): {:.1}% ± {:.1}%", ai_number + 1, difficulty, win_rate * 100.0, interval * 100.0)?;
+        }
+
+        // Everyone plays from every seat equally often, so a seat winning more than its share is
+        // down to the seat.
+        let fair_share = 1.0 / self.seat_wins.len() as f64;
+        writeln!(f)?;
+        writeln!(f, "Wins by seat:")?;
+        for (seat, wins) in self.seat_wins.iter().enumerate() {
+            let (win_rate, interval) = self.win_rate(*wins);
+            writeln!(
+                f,
+                "  Seat {}: {:.1}% ± {:.1}%, {:+.1}% over an even share",
+                seat + 1,
+                win_rate * 100.0,
+                interval * 100.0,
+                (win_rate - fair_share) * 100.0,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    mod simulate {
+        use super::super::*;
+
+        #[test]
+        fn every_game_is_won_or_given_up_on() {
+            let config = Config { humans: 0, ais: 3, difficulties: vec![Difficulty::Easy, Difficulty::Medium], ..Config::default() };
+
+            let results = simulate(&config, 20, 3, &mut Ratings::default()).unwrap();
+
+            assert_eq!(results.games, 20);
+            assert_eq!(results.lineup, vec![Difficulty::Easy, Difficulty::Medium, Difficulty::Medium]);
+            assert_eq!(results.lineup_wins.iter().sum::<usize>() + results.unfinished, 20);
+            assert_eq!(results.seat_wins.iter().sum::<usize>() + results.unfinished, 20);
+            assert!(results.turns >= 20);
+        }
+
+        #[test]
+        fn rates_each_difficulty() {
+            let config = Config { humans: 0, ais: 2, difficulties: vec![Difficulty::Easy, Difficulty::Hard], ..Config::default() };
+            let mut ratings = Ratings::default();
+
+            let results = simulate(&config, 10, 2, &mut ratings).unwrap();
+
+            assert_eq!(ratings.rating("easy").games, 10 - results.unfinished);
+            assert_eq!(ratings.rating("hard").games, 10 - results.unfinished);
+        }
+
+        #[test]
+        fn more_threads_than_games_is_fine() {
+            let config = Config { humans: 0, ais: 2, ..Config::default() };
+
+            let results = simulate(&config, 2, 8, &mut Ratings::default()).unwrap();
+
+            assert_eq!(results.games, 2);
+        }
+    }
+
+    mod confidence_interval {
+        use super::super::*;
+
+        #[test]
+        fn narrows_with_more_games() {
+            assert!((confidence_interval(0.5, 100) - 0.098).abs() < 0.001);
+            assert!(confidence_interval(0.5, 10_000) < confidence_interval(0.5, 100));
+        }
+
+        #[test]
+        fn is_zero_when_every_game_goes_the_same_way() {
+            assert_eq!(confidence_interval(1.0, 50), 0.0);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! This mod is for tuning the weights tuned computer players play by, by having them play
+//! themselves.
+
+use rand::Rng;
+
+use crate::{
+    ai::{LinearStrategy, Weights},
+    game::{Rules, Uno},
+    simulate::{confidence_interval, MAX_TURNS},
+};
+
+/// How far weights get nudged each round, compared to how big they already are.
+const STEP: f64 = 0.3;
+
+/// How one round of tuning went.
+#[derive(Debug, PartialEq)]
+pub struct Round {
+    /// Counting from 1.
+    pub number: usize,
+    /// The share of games the nudged weights won against the best ones so far.
+    pub win_rate: f64,
+    /// Whether the nudged weights won clearly enough to take over.
+    pub improved: bool,
+}
+
+/// Hill climbs from `weights` over `rounds` rounds. Each round every weight is nudged a random
+/// amount, and the nudged weights play `players` player games of `rules` against the best weights
+/// so far. Every one of `deals` deals is played once with the nudged weights in each seat, so
+/// neither side gets the better cards. Nudged weights become the best ones when they win more than
+/// their share of games by more than luck would explain, going by the 95% confidence interval.
+/// `report` hears how each round went, along with the best weights after it.
+pub fn tune(
+    rules: Rules,
+    players: usize,
+    mut weights: Weights,
+    rounds: usize,
+    deals: usize,
+    rng: &mut impl Rng,
+    mut report: impl FnMut(&Round, &Weights),
+) -> Weights {
+    for number in 1..=rounds {
+        let nudged = nudge(&weights, rng);
+        let seeds: Vec<u64> = (0..deals).map(|_| rng.gen()).collect();
+        let wins = seeds.iter()
+            .flat_map(|seed| (0..players).map(move |seat| (*seed, seat)))
+            .filter(|(seed, seat)| play_game(rules, players, *seed, *seat, &nudged, &weights) == Some(*seat))
+            .count();
+        let games = (deals * players).max(1);
+        let win_rate = wins as f64 / games as f64;
+        let improved = win_rate - confidence_interval(win_rate, games) > 1.0 / players as f64;
+        if improved {
+            weights = nudged;
+        }
+        report(&Round { number, win_rate, improved }, &weights);
+    }
+    weights
+}
+
+/// Moves every weight up or down by a random amount. Weights near zero still move a bit, so
+/// features that start out ignored can come to matter.
+fn nudge(weights: &Weights, rng: &mut impl Rng) -> Weights {
+    Weights(weights.0.map(|weight| weight + rng.gen_range(-STEP..STEP) * (weight.abs() + 1.0)))
+}
+
+/// Plays one game dealt from `seed` with `challenger` in `seat` and `best` everywhere else, and
+/// gives back who won, if anyone did.
+fn play_game(rules: Rules, players: usize, seed: u64, seat: usize, challenger: &Weights, best: &Weights) -> Option<usize> {
+    let mut uno = Uno::seeded(0, players as i32, rules, seed);
+    for player in 0..players {
+        let weights = if player == seat { challenger } else { best };
+        uno.set_strategy(player, Box::new(LinearStrategy { weights: weights.clone() }));
+    }
+    let mut turns = 0;
+    while !uno.game_over() && turns < MAX_TURNS {
+        uno.automate_current_player_turn();
+        turns += 1;
+    }
+    uno.winning_player_index().map(|player| player as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    mod tune {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use super::super::*;
+
+        #[test]
+        fn only_keeps_weights_that_clearly_win_more_than_their_share() {
+            let mut rng = StdRng::seed_from_u64(1);
+            let mut best = Weights::default();
+
+            let tuned = tune(Rules::default(), 2, Weights::default(), 5, 10, &mut rng, |round, weights| {
+                assert_eq!(round.improved, round.win_rate - confidence_interval(round.win_rate, 20) > 0.5);
+                assert_eq!(round.improved, *weights != best);
+                best = weights.clone();
+            });
+
+            assert_eq!(tuned, best);
+        }
+
+        #[test]
+        fn plays_out_the_same_way_with_the_same_seed() {
+            let tune_with_seed = |seed| {
+                let mut rounds = Vec::new();
+                let weights = tune(Rules::default(), 3, Weights::default(), 2, 5, &mut StdRng::seed_from_u64(seed), |round, _| {
+                    rounds.push(round.win_rate);
+                });
+                (weights, rounds)
+            };
+
+            assert_eq!(tune_with_seed(4), tune_with_seed(4));
+        }
+    }
+
+    mod nudge {
+        use rand::thread_rng;
+
+        use super::super::*;
+
+        #[test]
+        fn moves_weights_a_little() {
+            let weights = Weights::default();
+
+            let nudged = nudge(&weights, &mut thread_rng());
+
+            for (weight, nudged_weight) in weights.0.iter().zip(nudged.0) {
+                assert!((nudged_weight - weight).abs() <= STEP * (weight.abs() + 1.0));
+            }
+        }
+    }
+}
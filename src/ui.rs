@@ -1,12 +1,44 @@
-use crate::card::Card;
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use crate::{
+    ai::Explanation,
+    card::{Card, CardFace, Color, Effect, Side},
+};
 
 #[derive(Default)]
 pub struct UI {
-    pub last_turn_recap: Option<TurnRecap>,
+    /// Every turn since the player whose hand is shown last moved, in order.
+    pub turn_recaps: Vec<TurnRecap>,
+    /// Why each computer player made their moves since the human last did, by player number.
+    /// Only shown when asked for.
+    pub explanations: Vec<(i32, Explanation)>,
+    /// The move suggested to the player whose hand is shown, along with the index of the card it
+    /// plays, if it plays one.
+    pub hint: Option<(Option<usize>, Explanation)>,
+    /// Whether players can ask for hints.
+    pub hints: bool,
+    /// Set while computer players' moves are being shown one at a time, when there's nothing for
+    /// the human to do yet.
+    pub watching: bool,
     pub player_instruction: Option<PlayerInstruction>,
     pub uno_declarations: Vec<i32>,
+    pub knocked_out_players: Vec<i32>,
+    /// Players who ran out of cards in a last player standing game, in the order they went out.
+    pub finished_players: Vec<i32>,
+    pub finishing_order: Option<Vec<i32>>,
     pub error: Option<String>,
     pub winning_player: Option<i32>,
+    pub winning_team: Option<i32>,
+    pub points_won: Option<i32>,
+    /// The last color each player signaled to their team.
+    pub signals: Vec<(i32, Color)>,
+    pub team_signals: bool,
+    /// Only set for Uno Flip games.
+    pub side: Option<Side>,
+    /// What customizable wilds do, if the game has them.
+    pub custom_wild_effect: Option<Effect>,
+    /// Each player's team, for team games.
+    pub teams: Option<Vec<i32>>,
     displayed_hand: Option<DisplayedHand>,
 }
 
@@ -15,43 +47,165 @@ impl UI {
         UI::clear_screen();
 
         if let Some(winning_player) = self.winning_player {
-            println!("Player {} won!", winning_player);
+            match self.winning_team {
+                Some(winning_team) => println!("Team {} won! Player {} went out.", winning_team, winning_player),
+                None => println!("Player {} won!", winning_player),
+            }
+            if let Some(points_won) = self.points_won {
+                println!("That's worth {} points.", points_won);
+            }
+            if let Some(finishing_order) = &self.finishing_order {
+                println!();
+                for (place, player) in finishing_order.iter().enumerate() {
+                    println!("{}) Player {}", place + 1, player);
+                }
+            }
             return;
         }
 
-        if let Some(last_turn_recap) = &self.last_turn_recap {
-            if last_turn_recap.drawn_cards > 0 {
-                println!("Player {} drew {} cards!", last_turn_recap.player, last_turn_recap.drawn_cards);
+        for turn_recap in &self.turn_recaps {
+            if turn_recap.drawn_cards > 0 {
+                println!("Player {} drew {} cards!", turn_recap.player, turn_recap.drawn_cards);
+            }
+            for played_card in &turn_recap.played_cards {
+                match played_card.color.filter(|_| played_card.wild) {
+                    Some(color) => {
+                        let uncolored_card = Card { color: None, ..*played_card };
+                        println!("Player {} played a {} and picked {}!", turn_recap.player, uncolored_card, color);
+                    }
+                    None => println!("Player {} played a {}!", turn_recap.player, played_card),
+                }
+            }
+            if turn_recap.reversed {
+                println!("Play changed direction!");
+            }
+            for (player, drawn_cards) in &turn_recap.forced_draws {
+                println!("Player {} had to draw {} cards!", player, drawn_cards);
+            }
+            for skipped_player in &turn_recap.skipped_players {
+                println!("Player {} was skipped!", skipped_player);
+            }
+            for challenged_player in &turn_recap.challenged_players {
+                println!("Player {} caught Player {} not calling Uno!", turn_recap.player, challenged_player);
+            }
+        }
+        if !self.turn_recaps.is_empty() {
+            println!();
+        }
+
+        for (player, explanation) in &self.explanations {
+            println!("Player {} chose to {}.", player, explanation.action);
+            for reason in &explanation.reasons {
+                println!("  {}", reason);
             }
-            for played_card in &last_turn_recap.played_cards {
-                println!("Player {} played a {}!", last_turn_recap.player, played_card);
+            if !explanation.scores.is_empty() {
+                println!("  What it weighed up:");
+                for (action, score) in &explanation.scores {
+                    println!("    {}: {:.1}", action, score);
+                }
             }
             println!();
         }
 
-        if self.uno_declarations.len() > 0 {
+        if !self.finished_players.is_empty() {
+            for (place, player) in self.finished_players.iter().enumerate() {
+                println!("Player {} went out in place {}!", player, place + 1);
+            }
+            println!();
+        }
+
+        if !self.knocked_out_players.is_empty() {
+            for player in &self.knocked_out_players {
+                println!("Player {} was knocked out!", player);
+            }
+            println!();
+        }
+
+        if !self.signals.is_empty() {
+            for (player, color) in &self.signals {
+                println!("Player {} signaled {} to their team.", player, color);
+            }
+            println!();
+        }
+
+        if !self.uno_declarations.is_empty() {
             for player in &self.uno_declarations {
                 println!("Player {} has uno!", player);
             }
             println!();
         }
 
+        if let Some(custom_wild_effect) = self.custom_wild_effect {
+            println!("Customizable wilds: {}", custom_wild_effect);
+            println!();
+        }
+
+        if let Some(side) = self.side {
+            println!("The {} side is showing.", side);
+            println!();
+        }
+
         if let Some(displayed_hand) = &self.displayed_hand {
-            println!("Player {}'s cards:", displayed_hand.player);
+            match displayed_hand.team {
+                Some(team) => println!("Player {}'s cards (team {}):", displayed_hand.player, team),
+                None => println!("Player {}'s cards:", displayed_hand.player),
+            }
+            let hinted_card = self.hint.as_ref().and_then(|(card_index, _)| *card_index);
             for (i, card) in displayed_hand.cards.iter().enumerate() {
-                println!("{}) {}", 1 + i, card);
+                if hinted_card == Some(i) {
+                    println!("{}) {}  <- hint", 1 + i, card);
+                } else {
+                    println!("{}) {}", 1 + i, card);
+                }
+            }
+            println!();
+            for (other_player, backs) in &displayed_hand.other_backs {
+                let backs: Vec<String> = backs.iter().map(|back| Card::from(*back).to_string()).collect();
+                println!("The backs of Player {}'s cards: {}", other_player, backs.join(", "));
+            }
+            if !displayed_hand.other_backs.is_empty() {
+                println!();
+            }
+        }
+
+        if let Some((_, explanation)) = &self.hint {
+            println!("Hint: {}.", explanation.action);
+            for reason in &explanation.reasons {
+                println!("  {}", reason);
             }
             println!();
         }
 
+        if self.watching {
+            return;
+        }
+
         if let Some(player_instruction) = &self.player_instruction {
             match player_instruction {
                 PlayerInstruction::PickCard => {
-                    println!("Type a number to play a card, or \"d\" to draw a card: ")
+                    println!("Type a number to play a card, or \"d\" to draw a card: ");
+                    println!("After drawing, \"p\" passes. \"u\" calls Uno, and \"c 2\" catches Player 2 not calling it.");
+                    println!("\"e\" explains why the computer players made their last moves.");
+                    if self.hints {
+                        println!("\"h\" gives you a hint.");
+                    }
+                    if self.team_signals {
+                        println!("You can also signal a color to your team, like \"s red\".");
+                    }
+                },
+                PlayerInstruction::PickPlayerToSwapWith => {
+                    println!("Type the number of the player you want to swap hands with: ")
+                },
+                PlayerInstruction::StackOrDraw(num_cards) => {
+                    println!("Type a number to stack a draw card, or \"d\" to draw {} cards: ", num_cards)
                 },
                 PlayerInstruction::PickWildColor => {
                     println!("What color do you want your wild card to be?");
-                    println!("Enter one of \"R\", \"B\", \"G\", or \"Y\" to pick a color: ");
+                    if self.side == Some(Side::Dark) {
+                        println!("Enter one of \"pink\", \"teal\", \"orange\", or \"purple\" to pick a color: ");
+                    } else {
+                        println!("Enter one of \"R\", \"B\", \"G\", or \"Y\" to pick a color: ");
+                    }
                 },
             }
         }
@@ -69,28 +223,86 @@ impl UI {
         print!("\x1B[H");
     }
 
-    pub fn display_hand(&mut self, player: i32, cards: &Vec<Card>) {
+    /// Shows a player their cards, along with the backs of everyone else's cards in Uno Flip.
+    pub fn display_hand(&mut self, player: i32, cards: &[Card], backs: &[Vec<CardFace>]) {
         self.displayed_hand = Some(
             DisplayedHand {
                 player,
-                cards: cards.clone(),
+                team: self.teams.as_ref().map(|teams| teams[(player - 1) as usize]),
+                cards: cards.to_vec(),
+                other_backs: backs.iter().enumerate()
+                    .filter(|(_, backs)| !backs.is_empty())
+                    .map(|(seat, backs)| (seat as i32 + 1, backs.clone()))
+                    .collect(),
             }
         )
     }
 }
 
+/// What happened on one player's turn.
+#[derive(Default)]
 pub struct TurnRecap {
     pub player: i32,
     pub played_cards: Vec<Card>,
     pub drawn_cards: i32,
+    /// Players caught not calling Uno.
+    pub challenged_players: Vec<i32>,
+    /// Other players the turn made draw, and how many cards each drew.
+    pub forced_draws: Vec<(i32, i32)>,
+    /// Players who lost their turn to it.
+    pub skipped_players: Vec<i32>,
+    /// Whether a card played turned play around.
+    pub reversed: bool,
 }
 
 pub enum PlayerInstruction {
     PickCard,
-    PickWildColor
+    PickWildColor,
+    PickPlayerToSwapWith,
+    /// A draw penalty has stacked up on the player.
+    StackOrDraw(i32),
 }
 
 pub struct DisplayedHand {
     pub player: i32,
-    pub cards: Vec<Card>
+    pub team: Option<i32>,
+    pub cards: Vec<Card>,
+    /// The backs of other players' cards, by player number.
+    pub other_backs: Vec<(i32, Vec<CardFace>)>,
+}
+
+/// How computer players' moves are shown to humans.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum Pace {
+    /// All at once, summed up before the human's next turn.
+    #[default]
+    FastForward,
+    /// One at a time, waiting this long after each.
+    Delay(Duration),
+    /// One at a time, waiting for the human to press enter after each.
+    Keypress,
+}
+
+impl Display for Pace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pace::FastForward => write!(f, "fast-forward"),
+            Pace::Delay(delay) => write!(f, "{}", delay.as_millis()),
+            Pace::Keypress => write!(f, "keypress"),
+        }
+    }
+}
+
+impl FromStr for Pace {
+    type Err = String;
+
+    fn from_str(pace: &str) -> Result<Self, Self::Err> {
+        match pace {
+            "fast-forward" => Ok(Pace::FastForward),
+            "keypress" => Ok(Pace::Keypress),
+            _ => pace.parse().map(|millis| Pace::Delay(Duration::from_millis(millis))).map_err(|_| {
+                format!("\"{}\" isn't a pace, pick \"fast-forward\", \"keypress\", or a number of milliseconds.", pace)
+            }),
+        }
+    }
 }
@@ -1,4 +1,6 @@
-use std::io;
+use std::{io, thread};
+
+use crate::ui::Pace;
 
 pub fn get_user_input() -> Option<Input> {
     let mut user_input = String::new();
@@ -10,6 +12,22 @@ pub fn get_user_input() -> Option<Input> {
     }
 }
 
+/// Waits after showing a computer player's move, going by `pace`. Returns true if the human asked
+/// to fast-forward through the rest of the computer players' moves.
+pub fn wait_for_next_move(pace: Pace) -> bool {
+    match pace {
+        Pace::FastForward => true,
+        Pace::Delay(delay) => {
+            thread::sleep(delay);
+            false
+        }
+        Pace::Keypress => {
+            println!("Press enter to see the next move, or type \"f\" to fast-forward to your turn.");
+            matches!(get_user_input(), Some(Input::Text(text)) if text.eq_ignore_ascii_case("f"))
+        }
+    }
+}
+
 #[derive(std::fmt::Debug)]
 pub enum Input {
     Text(String),
@@ -0,0 +1,147 @@
+//! This mod is for what a single player can see of the game.
+
+use serde::Serialize;
+
+use crate::{
+    card::{Card, CardFace, Color, Effect, Side},
+    game::{can_play_card, can_stack_card, Action, Event, PlayerStatus, Rules, TurnOrder},
+    profile::Profile,
+};
+
+/// The game from one player's seat, which is what strategies decide their moves from. It only has
+/// what that player could know sitting at the table, so nobody can peek at cards they shouldn't.
+#[derive(Clone, Serialize)]
+pub struct PlayerView<'a> {
+    pub seat: usize,
+    /// Whose turn it is.
+    pub current_player: usize,
+    /// This player's cards. In Uno Flip they can't see the backs of their own cards, so the cards
+    /// have no `flip_side`.
+    pub hand: Vec<Card>,
+    /// The backs of everyone else's cards, which the whole table can see in Uno Flip. This
+    /// player's own backs are left empty.
+    pub backs: Vec<Vec<CardFace>>,
+    /// The card on top of the discard pile. Its back is face down on the pile, so it has no
+    /// `flip_side`.
+    pub top_card: Option<Card>,
+    /// How many cards every player is holding, including this player.
+    pub hand_sizes: Vec<usize>,
+    pub statuses: Vec<PlayerStatus>,
+    pub teams: Vec<Option<i32>>,
+    /// The last color each player signaled to their team.
+    pub signals: Vec<Option<Color>>,
+    pub signaled_this_turn: bool,
+    pub drew_this_turn: bool,
+    pub called_uno: bool,
+    /// Which players got down to one card without calling Uno, and can be challenged for it.
+    pub missed_uno_calls: Vec<bool>,
+    pub turn_order: TurnOrder,
+    /// Who goes after this player if nothing changes the turn order, which also says which way
+    /// play is going.
+    pub next_player: usize,
+    /// Who'd go after this player if the turn order was reversed.
+    pub previous_player: usize,
+    /// Cards this player has to draw unless they stack another draw card on top.
+    pub pending_draw: i32,
+    /// How many cards are left to draw before the discard pile gets shuffled back in.
+    pub deck_size: usize,
+    /// How many cards are in the discard pile, counting the top card.
+    pub discard_size: usize,
+    pub side: Side,
+    pub rules: Rules,
+    /// Everything that's happened in the game so far, oldest first.
+    pub history: &'a [Event],
+    /// How each seat played in earlier games, for the humans who've been seen before. Empty if
+    /// nobody has.
+    pub profiles: &'a [Profile],
+}
+
+impl PlayerView<'_> {
+    /// The indexes of every card in this player's hand they're allowed to play right now.
+    pub fn playable_card_indexes(&self) -> Vec<usize> {
+        (0..self.hand.len())
+            .filter(|card_index| {
+                let card = &self.hand[*card_index];
+                (self.pending_draw == 0 || can_stack_card(self.top_card.as_ref(), card)) && can_play_card(self.top_card.as_ref(), card)
+            })
+            .collect()
+    }
+
+    /// Everything this player is allowed to do right now. This is what the game checks actions
+    /// against, so it's the one place the rules about what can be done live. Players can only
+    /// challenge when it isn't their turn.
+    pub fn legal_actions(&self) -> Vec<Action> {
+        if self.statuses[self.seat] != PlayerStatus::Playing {
+            return Vec::new();
+        }
+        let challenges = (0..self.missed_uno_calls.len())
+            .filter(|target| *target != self.seat && self.missed_uno_calls[*target])
+            .map(Action::Challenge);
+        if self.seat != self.current_player {
+            return challenges.collect();
+        }
+
+        let colors = Color::for_side(self.side);
+        let players_to_swap_with: Vec<Option<usize>> = (0..self.statuses.len())
+            .filter(|seat| *seat != self.seat && self.statuses[*seat] == PlayerStatus::Playing)
+            .map(Some)
+            .collect();
+
+        let mut actions = Vec::new();
+        for card_index in self.playable_card_indexes() {
+            let card = &self.hand[card_index];
+            let card_colors: Vec<Option<Color>> = if card.wild { colors.iter().copied().map(Some).collect() } else { vec![None] };
+            let swap_targets: &[Option<usize>] = if self.rules.card_effects(card).contains(&Effect::SwapHands) { &players_to_swap_with } else { &[None] };
+            for color in card_colors {
+                for swap_with in swap_targets {
+                    actions.push(Action::Play { card_index, color, swap_with: *swap_with });
+                }
+            }
+        }
+        if self.can_draw() {
+            actions.push(Action::Draw);
+        }
+        // With every card in someone's hand there's nothing to draw, so players stuck without a
+        // card to play have to pass.
+        if (self.drew_this_turn || !self.can_draw()) && self.pending_draw == 0 {
+            actions.push(Action::Pass);
+        }
+        if self.hand.len() == 2 && !self.called_uno {
+            actions.push(Action::CallUno);
+        }
+        actions.extend(challenges);
+        if self.rules.team_signals && self.teams[self.seat].is_some() && !self.signaled_this_turn {
+            actions.extend(colors.iter().copied().map(Action::Signal));
+        }
+        actions
+    }
+
+    /// Whether there's anything for this player to draw. Penalties can always be taken, since
+    /// they're limited to what's left to draw.
+    pub fn can_draw(&self) -> bool {
+        self.pending_draw > 0 || self.deck_size + self.discard_size > 0
+    }
+
+    /// Who'd go next if this player played `card`, ignoring skips.
+    pub fn next_player_after(&self, card: &Card) -> usize {
+        if self.rules.card_effects(card).contains(&Effect::Reverse) {
+            self.previous_player
+        } else {
+            self.next_player
+        }
+    }
+
+    /// Whether another player is on this player's team.
+    pub fn is_teammate(&self, player: usize) -> bool {
+        let team = self.teams[self.seat];
+        player != self.seat && team.is_some() && team == self.teams[player]
+    }
+
+    /// The color a teammate has asked for, if it's one that can still be picked.
+    pub fn teammate_signal(&self) -> Option<Color> {
+        (0..self.signals.len())
+            .filter(|player| self.is_teammate(*player))
+            .find_map(|player| self.signals[player])
+            .filter(|color| color.side() == self.side)
+    }
+}